@@ -0,0 +1,139 @@
+use crate::export::{ExportError, ExportManager};
+use crate::flac::encode_flac;
+use crate::models::*;
+use std::f32::consts::TAU;
+
+/// Plenty for cue tones and keeps a 30 minute WAV around 60 MB
+pub const SAMPLE_RATE: u32 = 16_000;
+// Countdown ticks before each pour and drain
+const LEAD_IN_TICKS: u32 = 3;
+const TAIL_SECONDS: f32 = 2.0;
+// Fade in and out of every tone so it starts and stops without a click
+const FADE_SECONDS: f32 = 0.005;
+
+const CUE_VOLUME: f32 = 0.5;
+const TICK_VOLUME: f32 = 0.25;
+
+impl ExportManager {
+    pub fn export_audio(
+        &self,
+        plan: &ProcessPlan,
+        format: AudioFormat,
+        file_path: Option<String>,
+    ) -> Result<String, ExportError> {
+        let samples = render_process_audio(plan);
+
+        let (content, label, extension) = match format {
+            AudioFormat::Wav => (encode_wav(&samples, SAMPLE_RATE), "WAV", "wav"),
+            AudioFormat::Flac => (encode_flac(&samples, SAMPLE_RATE), "FLAC", "flac"),
+        };
+
+        let path = self.write_file(file_path.as_deref(), &[&plan.name], extension, &content)?;
+        Ok(format!("Exported process audio to {} file: {}", label, path))
+    }
+}
+
+/// The whole process as mono 16-bit audio: a chime at the start, countdown ticks
+/// and distinct tones for every pour, agitation and drain, and a closing chime
+pub fn render_process_audio(plan: &ProcessPlan) -> Vec<i16> {
+    let total_seconds = plan.total_seconds() as f32;
+    let mut buffer = vec![0f32; ((total_seconds + TAIL_SECONDS) * SAMPLE_RATE as f32) as usize];
+
+    // Rising chime for the start, which also stands in for the first pour
+    for (index, frequency) in [523.0, 659.0, 784.0].into_iter().enumerate() {
+        tone(&mut buffer, 0.15 * index as f32, 0.15, frequency, frequency, CUE_VOLUME);
+    }
+
+    for action in plan.actions() {
+        let start = action.start_seconds as f32;
+        let end = start + action.duration_seconds as f32;
+
+        match action.kind {
+            ProcessActionKind::Pour if action.start_seconds == 0 => {},
+            ProcessActionKind::Pour => {
+                lead_in(&mut buffer, start);
+                tone(&mut buffer, start, 0.15, 880.0, 880.0, CUE_VOLUME);
+                tone(&mut buffer, start + 0.25, 0.15, 880.0, 880.0, CUE_VOLUME);
+            },
+            ProcessActionKind::Agitate => {
+                for beep in 0..3 {
+                    tone(&mut buffer, start + 0.14 * beep as f32, 0.08, 1320.0, 1320.0, CUE_VOLUME);
+                }
+                // Lower single beep to stop agitating
+                tone(&mut buffer, end - 0.12, 0.12, 660.0, 660.0, CUE_VOLUME);
+            },
+            ProcessActionKind::Drain => {
+                lead_in(&mut buffer, start);
+                tone(&mut buffer, start, 0.5, 880.0, 440.0, CUE_VOLUME);
+            },
+        }
+    }
+
+    // Falling chime with a held last note for the end
+    for (index, frequency) in [784.0, 659.0].into_iter().enumerate() {
+        tone(&mut buffer, total_seconds + 0.15 * index as f32, 0.15, frequency, frequency, CUE_VOLUME);
+    }
+    tone(&mut buffer, total_seconds + 0.3, 0.6, 523.0, 523.0, CUE_VOLUME);
+
+    buffer
+        .into_iter()
+        .map(|sample| (sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16)
+        .collect()
+}
+
+pub fn encode_wav(samples: &[i16], sample_rate: u32) -> Vec<u8> {
+    let data_length = (samples.len() * 2) as u32;
+    let mut output = Vec::with_capacity(44 + samples.len() * 2);
+
+    output.extend_from_slice(b"RIFF");
+    output.extend_from_slice(&(36 + data_length).to_le_bytes());
+    output.extend_from_slice(b"WAVE");
+
+    output.extend_from_slice(b"fmt ");
+    output.extend_from_slice(&16u32.to_le_bytes());
+    output.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    output.extend_from_slice(&1u16.to_le_bytes()); // mono
+    output.extend_from_slice(&sample_rate.to_le_bytes());
+    output.extend_from_slice(&(sample_rate * 2).to_le_bytes()); // bytes per second
+    output.extend_from_slice(&2u16.to_le_bytes()); // bytes per sample
+    output.extend_from_slice(&16u16.to_le_bytes());
+
+    output.extend_from_slice(b"data");
+    output.extend_from_slice(&data_length.to_le_bytes());
+    for sample in samples {
+        output.extend_from_slice(&sample.to_le_bytes());
+    }
+
+    output
+}
+
+// One tick a second for the last few seconds before an action
+fn lead_in(buffer: &mut [f32], start: f32) {
+    for tick in 1..=LEAD_IN_TICKS {
+        let at = start - tick as f32;
+        if at > 0.0 {
+            tone(buffer, at, 0.04, 1000.0, 1000.0, TICK_VOLUME);
+        }
+    }
+}
+
+// Adds a sine tone to the buffer, sweeping linearly between the two frequencies
+fn tone(buffer: &mut [f32], start: f32, duration: f32, from_hz: f32, to_hz: f32, volume: f32) {
+    let rate = SAMPLE_RATE as f32;
+    let first = (start.max(0.0) * rate) as usize;
+    let count = (duration * rate) as usize;
+    let fade = (FADE_SECONDS * rate).max(1.0);
+
+    let mut phase = 0f32;
+    for offset in 0..count {
+        let Some(sample) = buffer.get_mut(first + offset) else {
+            break;
+        };
+
+        let progress = offset as f32 / count as f32;
+        let frequency = from_hz + (to_hz - from_hz) * progress;
+        let envelope = (offset as f32 / fade).min((count - offset) as f32 / fade).min(1.0);
+        *sample += phase.sin() * volume * envelope;
+        phase = (phase + TAU * frequency / rate) % TAU;
+    }
+}
@@ -0,0 +1,513 @@
+// darkroom: the calculator, catalogue, exports and process timer of the
+// desktop app from the command line, on the same engine and database code
+use darkroom_core::calculator::CalculationEngine;
+use darkroom_core::cues::clock;
+use darkroom_core::database::DatabaseManager;
+use darkroom_core::export::ExportManager;
+use darkroom_core::export_files::ExportFiles;
+use darkroom_core::models::*;
+use darkroom_core::timer::ProcessTimer;
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fmt::Display;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const DEFAULT_DATABASE: &str = "complete_database.json";
+const DATABASE_VARIABLE: &str = "DARKROOM_DATABASE";
+const DEFAULT_TEMPERATURE: i64 = 20;
+const DEFAULT_VOLUME: u32 = 500;
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+// Often enough that the countdown never skips a second
+const TIMER_REFRESH: Duration = Duration::from_millis(250);
+
+// Options that take no value
+const SWITCHES: [&str; 3] = ["json", "force", "help"];
+const RECIPE_OPTIONS: [&str; 3] = ["temperature", "push-pull", "volume"];
+
+const USAGE: &str = "\
+Usage: darkroom [--database FILE] <command> [options]
+
+Commands:
+  calculate FILM DEVELOPER   Development time and dilution
+  films                      List films
+  developers [FILM]          List developers, or the ones with times for a film
+  search TEXT                Search films and developers
+  validate [FILE]            Check a database file
+  export FILM DEVELOPER      Write a recipe card
+  chart FILM...              Write development charts
+  timer FILM DEVELOPER       Count down a development with agitation prompts
+
+Recipe options, for calculate, export and timer:
+  --temperature C            Developer temperature, 20 when not set
+  --push-pull STOPS          1 for a one stop push, -1 for a one stop pull
+  --volume ML                Working solution volume, 500 when not set
+
+Options:
+  --database FILE            complete_database.json or $DARKROOM_DATABASE when not set
+  --json                     Print JSON (calculate, films, developers, search, validate)
+  --type TYPE                films: black_white, color_negative or slide
+  --manufacturer NAME        films: from this manufacturer only
+  --iso-min N, --iso-max N   films: speed range
+  --limit N                  films, search: at most N results
+  --format FORMAT            export: pdf, markdown, html, json or csv, chart: pdf or html
+  --output PATH              export, chart: file or folder, the current folder when not set
+  --page-size SIZE           export: a4, letter or a6
+  --force                    export, chart: replace an existing file
+  --pre-soak SECONDS         timer: water pre-soak before the developer
+  --lead-in SECONDS          timer: warning before each pour and drain, 10 when not set
+";
+
+type Command = fn(&CalculationEngine, &Args) -> Result<(), String>;
+
+fn main() -> ExitCode {
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("darkroom: {}", message);
+            return ExitCode::from(2);
+        },
+    };
+    if args.flag("help") || args.positional.is_empty() {
+        print!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("darkroom: {}", message);
+            ExitCode::FAILURE
+        },
+    }
+}
+
+fn run(args: &Args) -> Result<(), String> {
+    let command: Command = match args.positional[0].as_str() {
+        "validate" => return validate(args),
+        "calculate" => calculate,
+        "films" => films,
+        "developers" => developers,
+        "search" => search,
+        "export" => export,
+        "chart" => chart,
+        "timer" => timer,
+        other => return Err(format!("Unknown command {}, see darkroom --help", other)),
+    };
+
+    let database = load_database(&database_path(args))?
+        .take_database()
+        .ok_or("Failed to extract database")?;
+    let mut engine = CalculationEngine::new();
+    engine.load_database(database);
+    command(&engine, args)
+}
+
+fn calculate(engine: &CalculationEngine, args: &Args) -> Result<(), String> {
+    args.allow(&[&RECIPE_OPTIONS[..], &["json"]])?;
+    let request = calculation_request(args)?;
+    let result = engine.calculate_development(request)
+        .map_err(|e| format!("Calculation failed: {}", e))?;
+    if args.flag("json") {
+        return print_json(&result);
+    }
+
+    println!("{} in {}", result.film_name, result.developer_name);
+    println!("Time:         {}", result.time_formatted);
+    println!("Temperature:  {}°C", result.temperature.normalize());
+    if result.push_pull != 0 {
+        println!("Push/pull:    {:+} stops", result.push_pull);
+    }
+    println!("Dilution:     {}", result.dilution);
+    println!("Developer:    {} ml", result.developer_amount);
+    println!("Water:        {} ml", result.water_amount);
+    for note in &result.notes {
+        println!("Note:         {}", note);
+    }
+    Ok(())
+}
+
+fn films(engine: &CalculationEngine, args: &Args) -> Result<(), String> {
+    args.allow(&[&["json", "type", "manufacturer", "iso-min", "iso-max", "limit"]])?;
+    let query = FilmQuery {
+        film_types: args.keyword("type")?.into_iter().collect(),
+        iso_min: args.number("iso-min")?,
+        iso_max: args.number("iso-max")?,
+        manufacturers: args.value("manufacturer").map(str::to_string).into_iter().collect(),
+        limit: args.number("limit")?,
+        ..FilmQuery::default()
+    };
+    let result = engine.query_films(&query)
+        .map_err(|e| format!("Failed to query films: {}", e))?;
+    if args.flag("json") {
+        return print_json(&result.films);
+    }
+
+    for film in &result.films {
+        println!("{:<32} {:<36} ISO {:<5} {}", film.key, film.name, film.iso, film.manufacturer);
+    }
+    Ok(())
+}
+
+fn developers(engine: &CalculationEngine, args: &Args) -> Result<(), String> {
+    args.allow(&[&["json"]])?;
+    let Some(film_key) = args.positional.get(1) else {
+        let database = engine.get_database()
+            .map_err(|e| format!("Failed to get developers: {}", e))?;
+        let developers: BTreeMap<_, _> = database.developers.iter().collect();
+        if args.flag("json") {
+            return print_json(&developers);
+        }
+        for (key, developer) in developers {
+            println!("{:<32} {:<36} {}", key, developer.name, developer.manufacturer);
+        }
+        return Ok(());
+    };
+
+    let film = engine.get_film_info(film_key)
+        .map_err(|e| format!("Failed to get film: {}", e))?;
+    let combinations: BTreeMap<_, _> = film.developers.iter().collect();
+    if args.flag("json") {
+        return print_json(&combinations);
+    }
+    for (key, data) in combinations {
+        let name = engine.get_developer_info(key).map(|developer| developer.name.as_str()).unwrap_or("");
+        println!("{:<32} {:<36} {}", key, name, data.dilution);
+    }
+    Ok(())
+}
+
+fn search(engine: &CalculationEngine, args: &Args) -> Result<(), String> {
+    args.allow(&[&["json", "limit"]])?;
+    let text = args.positional[1..].join(" ");
+    if text.is_empty() {
+        return Err("search needs some text to look for".to_string());
+    }
+    let limit = args.number("limit")?.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    let results = engine.search_catalogue(&text, limit)
+        .map_err(|e| format!("Search failed: {}", e))?;
+    if args.flag("json") {
+        return print_json(&results);
+    }
+
+    for result in &results {
+        let kind = match result.kind {
+            CatalogueKind::Film => "film",
+            CatalogueKind::Developer => "developer",
+        };
+        println!("{:<10} {:<32} {:<36} {}", kind, result.key, result.name, result.manufacturer);
+    }
+    Ok(())
+}
+
+// Loading runs the same structure checks as the app, then every film and
+// developer pairing is calculated at 20°C without push or pull
+fn validate(args: &Args) -> Result<(), String> {
+    args.allow(&[&["json"]])?;
+    let path = match args.positional.get(1) {
+        Some(path) => PathBuf::from(path),
+        None => database_path(args),
+    };
+    let db_manager = load_database(&path)?;
+    let stats = db_manager.get_stats().ok_or("Failed to extract database")?;
+    let database = db_manager.take_database().ok_or("Failed to extract database")?;
+
+    let mut combinations: Vec<(String, String)> = database.films
+        .iter()
+        .flat_map(|(film_key, film)| film.developers.keys().map(|key| (film_key.clone(), key.clone())))
+        .collect();
+    combinations.sort();
+    let mut engine = CalculationEngine::new();
+    engine.load_database(database);
+
+    let mut problems = Vec::new();
+    for (film_key, developer_key) in combinations {
+        let request = CalculationRequest {
+            film_key: film_key.clone(),
+            developer_key: developer_key.clone(),
+            temperature: Decimal::from(DEFAULT_TEMPERATURE),
+            push_pull: 0,
+            volume: DEFAULT_VOLUME,
+        };
+        if let Err(e) = engine.calculate_development(request) {
+            problems.push(format!("{} with {}: {}", film_key, developer_key, e));
+        }
+    }
+
+    if args.flag("json") {
+        print_json(&serde_json::json!({
+            "file": path.display().to_string(),
+            "stats": {
+                "film_count": stats.film_count,
+                "developer_count": stats.developer_count,
+                "total_combinations": stats.total_combinations,
+                "version": stats.version,
+                "last_updated": stats.last_updated
+            },
+            "problems": problems
+        }))?;
+    } else {
+        println!("{}: database version {}, updated {}", path.display(), stats.version, stats.last_updated);
+        println!(
+            "{} films, {} developers, {} combinations",
+            stats.film_count, stats.developer_count, stats.total_combinations
+        );
+        for problem in &problems {
+            println!("Problem: {}", problem);
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("{} of {} combinations do not calculate", problems.len(), stats.total_combinations))
+    }
+}
+
+fn export(engine: &CalculationEngine, args: &Args) -> Result<(), String> {
+    args.allow(&[&RECIPE_OPTIONS[..], &["format", "output", "page-size", "force"]])?;
+    let request = plan_request(args, 0)?;
+    let format = args.keyword("format")?.unwrap_or(ExportFormat::Pdf);
+    let page_size = args.keyword("page-size")?.unwrap_or_default();
+    let card = engine.build_recipe_card(&request)
+        .map_err(|e| format!("Failed to build recipe card: {}", e))?;
+
+    let (files, file_path) = export_files(args)?;
+    let message = ExportManager::new()
+        .with_files(files)
+        .export_recipe(&card, &request.calculation, format, page_size, file_path)
+        .map_err(|e| format!("Export failed: {}", e))?;
+    println!("{}", message);
+    Ok(())
+}
+
+fn chart(engine: &CalculationEngine, args: &Args) -> Result<(), String> {
+    args.allow(&[&["format", "output", "force"]])?;
+    let film_keys = &args.positional[1..];
+    if film_keys.is_empty() {
+        return Err("chart needs at least one film".to_string());
+    }
+    let charts = film_keys
+        .iter()
+        .map(|film_key| engine.development_chart(film_key))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to build development chart: {}", e))?;
+    let format = args.keyword("format")?.unwrap_or(ExportFormat::Pdf);
+
+    let (files, file_path) = export_files(args)?;
+    let message = ExportManager::new()
+        .with_files(files)
+        .export_development_charts(&charts, format, file_path)
+        .map_err(|e| format!("Export failed: {}", e))?;
+    println!("{}", message);
+    Ok(())
+}
+
+// Runs in the foreground until the process is done, Ctrl+C stops it
+fn timer(engine: &CalculationEngine, args: &Args) -> Result<(), String> {
+    args.allow(&[&RECIPE_OPTIONS[..], &["pre-soak", "lead-in"]])?;
+    let request = plan_request(args, args.number("pre-soak")?.unwrap_or(0))?;
+    let plan = engine.build_process_plan(&request)
+        .map_err(|e| format!("Failed to build process plan: {}", e))?;
+    let timer_request = TimerRequest {
+        name: None,
+        plan,
+        lead_in_seconds: args.number("lead-in")?,
+    };
+    let (mut timer, started) = ProcessTimer::start(timer_request, Instant::now())
+        .map_err(|e| format!("Failed to start timer: {}", e))?;
+
+    // The status line is redrawn in place, which only makes sense on a terminal
+    let live = std::io::stdout().is_terminal();
+    println!("{}, {} in total", started.status.plan_name, clock(started.status.total_seconds));
+    show_event(&started, &timer.plan().steps, live);
+    while timer.is_active() {
+        thread::sleep(TIMER_REFRESH);
+        for event in timer.poll(Instant::now()) {
+            show_event(&event, &timer.plan().steps, live);
+        }
+    }
+    Ok(())
+}
+
+fn show_event(event: &TimerEvent, steps: &[ProcessStep], live: bool) {
+    let status = &event.status;
+    match (event.kind, &event.action) {
+        (TimerEventKind::Started | TimerEventKind::PhaseChanged, _) => {
+            clear_line(live);
+            println!("== {}, {} ==", status.phase_name, clock(status.phase_remaining_seconds));
+        },
+        (TimerEventKind::LeadIn, Some(action)) => {
+            clear_line(live);
+            let wait = action.start_seconds.saturating_sub(status.elapsed_seconds);
+            println!("   Get ready to {} in {}", action_text(action, steps), clock(wait));
+        },
+        (TimerEventKind::ActionDue, Some(action)) => {
+            clear_line(live);
+            // The bell makes the terminal beep or flash
+            println!("\x07>> Now {}", action_text(action, steps));
+        },
+        (TimerEventKind::Tick, _) if live => {
+            print!(
+                "\r   {}: {} left, {} in total ",
+                status.phase_name,
+                clock(status.phase_remaining_seconds),
+                clock(status.remaining_seconds)
+            );
+            let _ = std::io::stdout().flush();
+        },
+        (TimerEventKind::Completed, _) => {
+            clear_line(live);
+            println!("Done after {}", clock(status.elapsed_seconds));
+        },
+        _ => {},
+    }
+}
+
+fn clear_line(live: bool) {
+    if live {
+        print!("\r{:60}\r", "");
+    }
+}
+
+fn action_text(action: &ProcessAction, steps: &[ProcessStep]) -> String {
+    match action.kind {
+        ProcessActionKind::Pour => format!("pour {}", steps[action.step_index].name.to_lowercase()),
+        ProcessActionKind::Agitate => format!("agitate for {}s", action.duration_seconds),
+        ProcessActionKind::Drain => format!("drain {}", steps[action.step_index].name.to_lowercase()),
+    }
+}
+
+fn calculation_request(args: &Args) -> Result<CalculationRequest, String> {
+    let [film_key, developer_key] = [1, 2].map(|index| args.positional.get(index));
+    let (Some(film_key), Some(developer_key)) = (film_key, developer_key) else {
+        return Err(format!("{} needs a film and a developer", args.positional[0]));
+    };
+
+    Ok(CalculationRequest {
+        film_key: film_key.clone(),
+        developer_key: developer_key.clone(),
+        temperature: args.number("temperature")?.unwrap_or(Decimal::from(DEFAULT_TEMPERATURE)),
+        push_pull: args.number("push-pull")?.unwrap_or(0),
+        volume: args.number("volume")?.unwrap_or(DEFAULT_VOLUME),
+    })
+}
+
+fn plan_request(args: &Args, pre_soak_seconds: u32) -> Result<ProcessPlanRequest, String> {
+    Ok(ProcessPlanRequest {
+        calculation: calculation_request(args)?,
+        label: None,
+        pre_soak_seconds,
+    })
+}
+
+// Writes to --output, or the current folder when not set. The folder the
+// user named is approved for this run.
+fn export_files(args: &Args) -> Result<(ExportFiles, Option<String>), String> {
+    let current = env::current_dir().map_err(|e| format!("Failed to read current folder: {}", e))?;
+    let output = args.value("output").map(|output| current.join(output));
+    let directories = output
+        .iter()
+        .map(|path| match path.parent() {
+            Some(parent) if !path.is_dir() => parent.to_path_buf(),
+            _ => path.clone(),
+        })
+        .collect();
+
+    let files = ExportFiles::new(current, directories).allow_overwrite(args.flag("force"));
+    Ok((files, output.map(|path| path.display().to_string())))
+}
+
+fn database_path(args: &Args) -> PathBuf {
+    args.value("database")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os(DATABASE_VARIABLE).map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_DATABASE))
+}
+
+fn load_database(path: &Path) -> Result<DatabaseManager, String> {
+    let mut db_manager = DatabaseManager::new();
+    db_manager.load_from_file(path)
+        .map_err(|e| format!("Failed to load database: {}", e))?;
+    Ok(db_manager)
+}
+
+fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+// Words in order, and --name value, --name=value or bare switches
+struct Args {
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+}
+
+impl Args {
+    fn parse(mut arguments: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut positional = Vec::new();
+        let mut options = HashMap::new();
+        while let Some(argument) = arguments.next() {
+            let Some(option) = argument.strip_prefix("--") else {
+                positional.push(argument);
+                continue;
+            };
+            let (name, value) = match option.split_once('=') {
+                Some((name, value)) => (name.to_string(), value.to_string()),
+                None if SWITCHES.contains(&option) => (option.to_string(), String::new()),
+                None => {
+                    let value = arguments.next().ok_or_else(|| format!("--{} needs a value", option))?;
+                    (option.to_string(), value)
+                },
+            };
+            options.insert(name, value);
+        }
+        Ok(Self { positional, options })
+    }
+
+    // Catches misspelled options, which would otherwise be silently ignored
+    fn allow(&self, groups: &[&[&str]]) -> Result<(), String> {
+        match self.options.keys().find(|name| {
+            name.as_str() != "database" && !groups.iter().any(|group| group.contains(&name.as_str()))
+        }) {
+            Some(name) => Err(format!("{} does not take --{}", self.positional[0], name)),
+            None => Ok(()),
+        }
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.options.contains_key(name)
+    }
+
+    fn value(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+
+    fn number<T: FromStr>(&self, name: &str) -> Result<Option<T>, String>
+    where
+        T::Err: Display,
+    {
+        self.value(name)
+            .map(|value| value.parse().map_err(|e| format!("Invalid --{} {}: {}", name, value, e)))
+            .transpose()
+    }
+
+    // Names as the app sends them, e.g. black_white or markdown
+    fn keyword<T: DeserializeOwned>(&self, name: &str) -> Result<Option<T>, String> {
+        self.value(name)
+            .map(|value| {
+                serde_json::from_value(serde_json::Value::String(value.to_string()))
+                    .map_err(|_| format!("Unknown --{} {}", name, value))
+            })
+            .transpose()
+    }
+}
@@ -1,8 +1,11 @@
+use crate::catalogue::datasheet_temperature;
 use crate::models::*;
+use crate::search::SearchIndex;
 use rust_decimal::Decimal;
 use std::collections::HashMap;
 use thiserror::Error;
 
+/// Why a calculation or catalogue lookup failed.
 #[derive(Error, Debug)]
 pub enum CalculationError {
     #[error("Film not found: {0}")]
@@ -21,23 +24,53 @@ pub enum CalculationError {
     DatabaseNotLoaded,
 }
 
+impl CalculationError {
+    /// A stable snake_case name for the error, e.g. `film_not_found`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            CalculationError::FilmNotFound(_) => "film_not_found",
+            CalculationError::DeveloperNotFound(_) => "developer_not_found",
+            CalculationError::CombinationNotSupported { .. } => "combination_not_supported",
+            CalculationError::InvalidTemperature(_) => "invalid_temperature",
+            CalculationError::InvalidPushPull(_) => "invalid_push_pull",
+            CalculationError::InvalidVolume(_) => "invalid_volume",
+            CalculationError::DatabaseNotLoaded => "database_not_loaded",
+        }
+    }
+}
+
+/// Development times, dilutions and catalogue queries over one loaded
+/// [`Database`]. Every method fails with [`CalculationError::DatabaseNotLoaded`]
+/// until [`CalculationEngine::load_database`] has been called.
+#[derive(Default)]
 pub struct CalculationEngine {
     database: Option<Database>,
+    search_index: SearchIndex,
 }
 
 impl CalculationEngine {
+    /// An engine with no database loaded.
     pub fn new() -> Self {
-        Self { database: None }
+        Self {
+            database: None,
+            search_index: SearchIndex::default(),
+        }
     }
 
+    /// Replaces the database and rebuilds the search index.
     pub fn load_database(&mut self, database: Database) {
+        self.search_index = SearchIndex::build(&database);
         self.database = Some(database);
     }
 
+    /// The loaded database.
     pub fn get_database(&self) -> Result<&Database, CalculationError> {
         self.database.as_ref().ok_or(CalculationError::DatabaseNotLoaded)
     }
 
+    /// Development time, dilution and chemistry amounts for one film and
+    /// developer. Temperature must be 15-30°C, push/pull -2 to +3 stops and
+    /// volume 100-2000 ml.
     pub fn calculate_development(&self, request: CalculationRequest) -> Result<CalculationResult, CalculationError> {
         let database = self.get_database()?;
         
@@ -104,7 +137,7 @@ impl CalculationEngine {
         Ok(())
     }
 
-    fn find_developer<'a>(&self, developers: &'a HashMap<String, Developer>, developer_key: &str) -> Result<&'a Developer, CalculationError> {
+    pub(crate) fn find_developer<'a>(&self, developers: &'a HashMap<String, Developer>, developer_key: &str) -> Result<&'a Developer, CalculationError> {
         // Try exact match first
         if let Some(developer) = developers.get(developer_key) {
             return Ok(developer);
@@ -131,7 +164,7 @@ impl CalculationEngine {
         Err(CalculationError::DeveloperNotFound(developer_key.to_string()))
     }
 
-    fn find_developer_data<'a>(&self, film: &'a Film, developer_key: &str) -> Result<&'a DeveloperData, CalculationError> {
+    pub(crate) fn find_developer_data<'a>(&self, film: &'a Film, developer_key: &str) -> Result<&'a DeveloperData, CalculationError> {
         // Try exact match first
         if let Some(data) = film.developers.get(developer_key) {
             return Ok(data);
@@ -149,7 +182,7 @@ impl CalculationEngine {
         })
     }
 
-    fn get_base_time(&self, film: &Film, dev_data: &DeveloperData, push_pull: i32) -> Result<Decimal, CalculationError> {
+    pub(crate) fn get_base_time(&self, film: &Film, dev_data: &DeveloperData, push_pull: i32) -> Result<Decimal, CalculationError> {
         let mut base_time = match film.film_type {
             FilmType::BlackWhite => {
                 dev_data.time_minutes
@@ -201,7 +234,36 @@ impl CalculationEngine {
         Ok(base_time)
     }
 
-    fn get_temperature_compensation(&self, temp_comp: &HashMap<String, Decimal>, temperature: Decimal) -> Decimal {
+    // Time published in the database for this push/pull, without any extrapolation
+    pub(crate) fn get_datasheet_time(&self, film: &Film, dev_data: &DeveloperData, push_pull: i32) -> Option<Decimal> {
+        match film.film_type {
+            FilmType::BlackWhite => match push_pull {
+                0 => dev_data.time_minutes.or(dev_data.time),
+                1 => dev_data.push_1_stop_minutes,
+                2 => dev_data.push_2_stop_minutes,
+                3 => dev_data.push_3_stop_minutes,
+                -1 => dev_data.pull_1_stop_minutes,
+                -2 => dev_data.pull_2_stop_minutes,
+                _ => None,
+            },
+            FilmType::ColorNegative => match push_pull {
+                0 => dev_data.developer_time_minutes,
+                1 => dev_data.push_1_stop_dev_time,
+                2 => dev_data.push_2_stop_dev_time,
+                -1 => dev_data.pull_1_stop_dev_time,
+                _ => None,
+            },
+            FilmType::Slide => match push_pull {
+                0 => dev_data.first_dev_time_minutes,
+                1 => dev_data.push_1_stop_first_dev_time,
+                2 => dev_data.push_2_stop_first_dev_time,
+                -1 => dev_data.pull_1_stop_first_dev_time,
+                _ => None,
+            },
+        }
+    }
+
+    pub(crate) fn get_temperature_compensation(&self, temp_comp: &HashMap<String, Decimal>, temperature: Decimal) -> Decimal {
         // Round to nearest 0.5 degree for lookup
         let rounded_temp = (temperature * Decimal::from(2)).round() / Decimal::from(2);
         let temp_key = rounded_temp.to_string();
@@ -234,7 +296,7 @@ impl CalculationEngine {
         Decimal::from(1)
     }
 
-    fn calculate_dilution(&self, dilution_str: &str, volume: u32, film_type: &FilmType) -> Result<(String, u32, u32), CalculationError> {
+    pub(crate) fn calculate_dilution(&self, dilution_str: &str, volume: u32, film_type: &FilmType) -> Result<(String, u32, u32), CalculationError> {
         match film_type {
             FilmType::BlackWhite => {
                 if dilution_str == "stock" || dilution_str == "1:0" {
@@ -248,12 +310,12 @@ impl CalculationEngine {
                 }
             },
             FilmType::ColorNegative | FilmType::Slide => {
-                Ok(("Ready to use".to_string(), volume, 0))
+                Ok((READY_TO_USE.to_string(), volume, 0))
             },
         }
     }
 
-    fn parse_dilution(&self, dilution_str: &str) -> Result<DilutionRatio, CalculationError> {
+    pub(crate) fn parse_dilution(&self, dilution_str: &str) -> Result<DilutionRatio, CalculationError> {
         let parts: Vec<&str> = dilution_str.split(':').collect();
         if parts.len() != 2 {
             return Ok(DilutionRatio { developer: 1, water: 0 }); // Default to stock
@@ -265,7 +327,7 @@ impl CalculationEngine {
         Ok(DilutionRatio { developer, water })
     }
 
-    fn format_time(&self, time_minutes: Decimal) -> String {
+    pub(crate) fn format_time(&self, time_minutes: Decimal) -> String {
         let total_seconds = (time_minutes * Decimal::from(60)).round();
         let minutes = total_seconds / Decimal::from(60);
         let seconds = total_seconds % Decimal::from(60);
@@ -302,11 +364,13 @@ impl CalculationEngine {
         notes
     }
 
+    /// Every film in the database, in no particular order.
     pub fn get_available_films(&self) -> Result<Vec<&Film>, CalculationError> {
         let database = self.get_database()?;
         Ok(database.films.values().collect())
     }
 
+    /// Keys of the developer and dilution combinations with data for a film.
     pub fn get_available_developers_for_film(&self, film_key: &str) -> Result<Vec<String>, CalculationError> {
         let database = self.get_database()?;
         let film = database.films.get(film_key)
@@ -315,14 +379,167 @@ impl CalculationEngine {
         Ok(film.developers.keys().cloned().collect())
     }
 
+    /// A film by key.
     pub fn get_film_info(&self, film_key: &str) -> Result<&Film, CalculationError> {
         let database = self.get_database()?;
         database.films.get(film_key)
             .ok_or_else(|| CalculationError::FilmNotFound(film_key.to_string()))
     }
 
+    /// A developer by key, or by a combination key such as `kodak_d76_stock`.
     pub fn get_developer_info(&self, developer_key: &str) -> Result<&Developer, CalculationError> {
         let database = self.get_database()?;
         self.find_developer(&database.developers, developer_key)
     }
-}
\ No newline at end of file
+
+    /// Films and developers matching a name, alias, manufacturer or ISO, best first.
+    pub fn search_catalogue(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>, CalculationError> {
+        self.get_database()?;
+        Ok(self.search_index.search(query, limit))
+    }
+
+    /// One film calculated for every developer, temperature and push/pull combination
+    /// requested. A combination that fails gets a cell with the error instead of stopping
+    /// the whole matrix. Empty lists fall back rather than giving an empty matrix: every
+    /// developer with data for the film, the datasheet temperature of each developer, and
+    /// normal development.
+    pub fn calculate_matrix(&self, request: &ComparisonRequest) -> Result<ComparisonMatrix, CalculationError> {
+        let film = self.get_film_info(&request.film_key)?;
+
+        let developer_keys = if request.developer_keys.is_empty() {
+            let mut keys: Vec<String> = film.developers.keys().cloned().collect();
+            keys.sort();
+            keys
+        } else {
+            request.developer_keys.clone()
+        };
+        let temperatures = if request.temperatures.is_empty() {
+            let mut temperatures: Vec<Decimal> = developer_keys
+                .iter()
+                .map(|key| film.developers.get(key).map(|data| datasheet_temperature(film, data)).unwrap_or(Decimal::from(20)))
+                .collect();
+            temperatures.sort();
+            temperatures.dedup();
+            temperatures
+        } else {
+            request.temperatures.clone()
+        };
+        let push_pull_values = if request.push_pull_values.is_empty() {
+            vec![0]
+        } else {
+            request.push_pull_values.clone()
+        };
+
+        let mut cells = Vec::with_capacity(developer_keys.len() * temperatures.len() * push_pull_values.len());
+        for developer_key in &developer_keys {
+            for &temperature in &temperatures {
+                for &push_pull in &push_pull_values {
+                    let calculation = self.calculate_development(CalculationRequest {
+                        film_key: request.film_key.clone(),
+                        developer_key: developer_key.clone(),
+                        temperature,
+                        push_pull,
+                        volume: request.volume,
+                    });
+
+                    let (result, error) = match calculation {
+                        Ok(result) => (Some(result), None),
+                        Err(e) => (None, Some(CalculationFailure {
+                            code: e.code().to_string(),
+                            message: e.to_string(),
+                        })),
+                    };
+
+                    cells.push(ComparisonCell {
+                        developer_key: developer_key.clone(),
+                        temperature,
+                        push_pull,
+                        result,
+                        error,
+                    });
+                }
+            }
+        }
+
+        Ok(ComparisonMatrix {
+            film_key: request.film_key.clone(),
+            film_name: film.name.clone(),
+            developer_keys,
+            temperatures,
+            push_pull_values,
+            cells,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::test_support::engine;
+
+    #[test]
+    fn developer_keys_resolve_with_dilution_and_stock_suffixes() {
+        let engine = engine();
+        for (key, expected) in [
+            ("kodak_d76", "Kodak D-76"),
+            ("kodak_d76_stock", "Kodak D-76"),
+            ("kodak_d76_1_1", "Kodak D-76"),
+            ("adox_rodinal_1_50", "Adox Rodinal"),
+            ("kodak_hc110_b", "Kodak HC-110"),
+        ] {
+            let developer = engine.get_developer_info(key).unwrap();
+            assert_eq!(developer.name, expected, "{}", key);
+        }
+    }
+
+    #[test]
+    fn unknown_developer_keys_are_not_guessed() {
+        let engine = engine();
+        for key in ["ilford_ilfosol3_1_9", "ilford_ilfosol31_14", "kodakd76", "kodak", "kodak_d7"] {
+            assert!(
+                matches!(engine.get_developer_info(key), Err(CalculationError::DeveloperNotFound(_))),
+                "{} should not resolve",
+                key
+            );
+        }
+    }
+
+    fn comparison(film_key: &str, developer_keys: &[&str], temperatures: &[i64], push_pull_values: &[i32]) -> ComparisonRequest {
+        ComparisonRequest {
+            film_key: film_key.to_string(),
+            developer_keys: developer_keys.iter().map(|key| key.to_string()).collect(),
+            temperatures: temperatures.iter().map(|&temperature| Decimal::from(temperature)).collect(),
+            push_pull_values: push_pull_values.to_vec(),
+            volume: 500,
+        }
+    }
+
+    #[test]
+    fn matrices_cover_every_combination_and_keep_failures_in_their_cell() {
+        let request = comparison("ilford_hp5_plus", &["kodak_d76_stock", "kodak_xtol_stock"], &[20, 24], &[0, 1, 5]);
+        let matrix = engine().calculate_matrix(&request).unwrap();
+
+        assert_eq!(matrix.cells.len(), 2 * 2 * 3);
+        let order: Vec<(&str, i32)> = matrix.cells[..3].iter().map(|cell| (cell.developer_key.as_str(), cell.push_pull)).collect();
+        assert_eq!(order, vec![("kodak_d76_stock", 0), ("kodak_d76_stock", 1), ("kodak_d76_stock", 5)]);
+        assert!(matrix.cells[0].result.is_some() && matrix.cells[0].error.is_none());
+        // Five stops is out of range, and HP5+ has no XTOL data at all
+        assert!(matrix.cells[2].result.is_none() && matrix.cells[2].error.is_some());
+        assert!(matrix.cells[6..].iter().all(|cell| cell.error.is_some()));
+    }
+
+    #[test]
+    fn empty_matrix_lists_fall_back_to_the_datasheet() {
+        let engine = engine();
+        let matrix = engine.calculate_matrix(&comparison("ilford_hp5_plus", &[], &[], &[])).unwrap();
+        assert_eq!(matrix.developer_keys.len(), engine.get_available_developers_for_film("ilford_hp5_plus").unwrap().len());
+        assert_eq!(matrix.temperatures, vec![Decimal::from(20)]);
+        assert_eq!(matrix.push_pull_values, vec![0]);
+        assert_eq!(matrix.cells.len(), matrix.developer_keys.len());
+        assert!(matrix.cells.iter().all(|cell| cell.result.is_some()));
+
+        let matrix = engine.calculate_matrix(&comparison("kodak_portra_160", &["kodak_flexicolor_c41"], &[], &[])).unwrap();
+        assert_eq!(matrix.temperatures, vec![Decimal::new(378, 1)]);
+        assert_eq!(matrix.cells.len(), 1);
+    }
+}
@@ -0,0 +1,249 @@
+use crate::calculator::{CalculationEngine, CalculationError};
+use crate::cues::clock;
+use crate::export::{ExportError, ExportManager};
+use crate::models::*;
+use crate::scheduler::SessionScheduler;
+use chrono::{DateTime, Days, Duration, Months, NaiveDate, Utc};
+
+const DEFAULT_REMINDER_SECONDS: u32 = 10;
+// RFC 5545 folds content lines longer than this many octets
+const MAX_LINE_OCTETS: usize = 75;
+
+impl CalculationEngine {
+    /// When each batch goes off. Batches of developers without a published stock
+    /// or working life are left out, there is no date to put on the calendar.
+    pub fn batch_expiries(&self, batches: &[ChemistryBatch]) -> Result<Vec<BatchExpiry>, CalculationError> {
+        let mut expiries = Vec::new();
+        for batch in batches {
+            let developer = self.find_developer(&self.get_database()?.developers, &batch.developer_key)?;
+            let expires_on = if batch.working_solution {
+                developer
+                    .working_life_hours
+                    .and_then(|hours| batch.mixed_on.checked_add_days(Days::new(u64::from(hours / 24))))
+            } else {
+                developer
+                    .stock_life_months
+                    .and_then(|months| batch.mixed_on.checked_add_months(Months::new(months)))
+            };
+
+            if let Some(expires_on) = expires_on {
+                expiries.push(BatchExpiry {
+                    label: batch.label.clone().unwrap_or_else(|| developer.name.clone()),
+                    developer_name: developer.name.clone(),
+                    mixed_on: batch.mixed_on,
+                    expires_on,
+                });
+            }
+        }
+
+        Ok(expiries)
+    }
+}
+
+impl ExportManager {
+    pub fn export_calendar(
+        &self,
+        request: &CalendarRequest,
+        expiries: &[BatchExpiry],
+        file_path: Option<String>,
+    ) -> Result<String, ExportError> {
+        let content = build_calendar(request, expiries, Utc::now())?;
+
+        let name = request.name.as_deref().unwrap_or("Darkroom session");
+        let path = self.write_file(file_path.as_deref(), &[name], "ics", content.as_bytes())?;
+        Ok(format!("Exported to iCalendar file: {}", path))
+    }
+}
+
+pub fn build_calendar(
+    request: &CalendarRequest,
+    expiries: &[BatchExpiry],
+    now: DateTime<Utc>,
+) -> Result<String, ExportError> {
+    let schedule = match (&request.schedule, &request.plan) {
+        (Some(schedule), _) => Some(schedule.clone()),
+        (None, Some(plan)) => Some(SessionScheduler::new(None).schedule(std::slice::from_ref(plan))),
+        (None, None) => None,
+    };
+    if schedule.is_none() && expiries.is_empty() {
+        return Err(ExportError::EmptyCalendar);
+    }
+
+    let name = request.name.clone().unwrap_or_else(|| "Darkroom session".to_string());
+    let mut calendar = CalendarWriter::new(&name, request.start, now);
+
+    if let Some(schedule) = &schedule {
+        let reminder = i64::from(request.reminder_seconds.unwrap_or(DEFAULT_REMINDER_SECONDS));
+        let several_tanks = schedule.tanks.len() > 1;
+
+        for tank in &schedule.tanks {
+            let reminders: Vec<&ScheduledAction> = schedule
+                .actions
+                .iter()
+                .filter(|scheduled| scheduled.plan_index == tank.plan_index)
+                .filter(|scheduled| matches!(scheduled.action.kind, ProcessActionKind::Pour | ProcessActionKind::Agitate))
+                .collect();
+            let alarm = |scheduled: &&ScheduledAction| {
+                let text = reminder_text(scheduled);
+                let text = if several_tanks { format!("{}: {}", tank.name, text) } else { text };
+                (i64::from(scheduled.action.start_seconds) - reminder, text)
+            };
+
+            match request.events {
+                CalendarEvents::Tanks => {
+                    let description: Vec<String> = tank
+                        .steps
+                        .iter()
+                        .map(|step| format!("{} {}", clock(step.start_seconds - tank.start_seconds), step.name))
+                        .collect();
+                    calendar.event(
+                        &format!("{}: {}", name, tank.name),
+                        tank.start_seconds,
+                        tank.end_seconds,
+                        &description.join("\n"),
+                        &reminders.iter().map(alarm).collect::<Vec<_>>(),
+                    );
+                },
+                CalendarEvents::Steps => {
+                    for step in &tank.steps {
+                        let summary = if several_tanks {
+                            format!("{}: {}", tank.name, step.name)
+                        } else {
+                            step.name.clone()
+                        };
+                        let alarms: Vec<(i64, String)> = reminders
+                            .iter()
+                            .filter(|scheduled| scheduled.action.step_index == step.step_index)
+                            .map(alarm)
+                            .collect();
+                        calendar.event(
+                            &summary,
+                            step.start_seconds,
+                            step.end_seconds,
+                            &format!("Step {} of {} in {}", step.step_index + 1, tank.steps.len(), tank.name),
+                            &alarms,
+                        );
+                    }
+                },
+            }
+        }
+    }
+
+    for expiry in expiries {
+        calendar.all_day_event(
+            &format!("{} expires", expiry.label),
+            expiry.expires_on,
+            &format!("{} mixed on {}", expiry.developer_name, expiry.mixed_on),
+        );
+    }
+
+    Ok(calendar.finish())
+}
+
+fn reminder_text(scheduled: &ScheduledAction) -> String {
+    match scheduled.action.kind {
+        ProcessActionKind::Pour => format!("Pour {}", scheduled.step_name.to_lowercase()),
+        ProcessActionKind::Agitate => format!("Agitate for {}s", scheduled.action.duration_seconds),
+        ProcessActionKind::Drain => format!("Drain {}", scheduled.step_name.to_lowercase()),
+    }
+}
+
+// Builds a VCALENDAR with CRLF line endings, escaped text and folded long lines
+struct CalendarWriter {
+    content: String,
+    start: DateTime<Utc>,
+    stamp: String,
+    uid_prefix: String,
+    next_uid: usize,
+}
+
+impl CalendarWriter {
+    fn new(name: &str, start: DateTime<Utc>, now: DateTime<Utc>) -> Self {
+        let mut writer = CalendarWriter {
+            content: String::new(),
+            start,
+            stamp: date_time(now),
+            uid_prefix: format!("{}-{}", date_time(start), now.timestamp_millis()),
+            next_uid: 1,
+        };
+        writer.line("BEGIN:VCALENDAR");
+        writer.line("VERSION:2.0");
+        writer.line("PRODID:-//DarkroomPro//Session Planner//EN");
+        writer.line("CALSCALE:GREGORIAN");
+        writer.line("METHOD:PUBLISH");
+        writer.line(&format!("X-WR-CALNAME:{}", escape(name)));
+        writer
+    }
+
+    // Offsets are seconds from the start of the session, alarms are (offset, text)
+    fn event(&mut self, summary: &str, start_seconds: u32, end_seconds: u32, description: &str, alarms: &[(i64, String)]) {
+        self.begin_event();
+        self.line(&format!("DTSTART:{}", date_time(self.at(i64::from(start_seconds)))));
+        self.line(&format!("DTEND:{}", date_time(self.at(i64::from(end_seconds)))));
+        self.line(&format!("SUMMARY:{}", escape(summary)));
+        self.line(&format!("DESCRIPTION:{}", escape(description)));
+        for (offset, text) in alarms {
+            self.line("BEGIN:VALARM");
+            self.line("ACTION:DISPLAY");
+            self.line(&format!("TRIGGER;VALUE=DATE-TIME:{}", date_time(self.at(*offset))));
+            self.line(&format!("DESCRIPTION:{}", escape(text)));
+            self.line("END:VALARM");
+        }
+        self.line("END:VEVENT");
+    }
+
+    fn all_day_event(&mut self, summary: &str, date: NaiveDate, description: &str) {
+        self.begin_event();
+        self.line(&format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")));
+        if let Some(next_day) = date.succ_opt() {
+            self.line(&format!("DTEND;VALUE=DATE:{}", next_day.format("%Y%m%d")));
+        }
+        self.line(&format!("SUMMARY:{}", escape(summary)));
+        self.line(&format!("DESCRIPTION:{}", escape(description)));
+        self.line("TRANSP:TRANSPARENT");
+        self.line("END:VEVENT");
+    }
+
+    fn begin_event(&mut self) {
+        let uid = format!("UID:{}-{}@darkroompro", self.uid_prefix, self.next_uid);
+        let stamp = format!("DTSTAMP:{}", self.stamp);
+        self.next_uid += 1;
+        self.line("BEGIN:VEVENT");
+        self.line(&uid);
+        self.line(&stamp);
+    }
+
+    fn at(&self, offset_seconds: i64) -> DateTime<Utc> {
+        self.start + Duration::seconds(offset_seconds)
+    }
+
+    fn line(&mut self, line: &str) {
+        let mut octets = 0;
+        for character in line.chars() {
+            // Continuation lines start with a space, which counts towards their length
+            if octets + character.len_utf8() > MAX_LINE_OCTETS {
+                self.content.push_str("\r\n ");
+                octets = 1;
+            }
+            self.content.push(character);
+            octets += character.len_utf8();
+        }
+        self.content.push_str("\r\n");
+    }
+
+    fn finish(mut self) -> String {
+        self.line("END:VCALENDAR");
+        self.content
+    }
+}
+
+fn date_time(value: DateTime<Utc>) -> String {
+    value.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
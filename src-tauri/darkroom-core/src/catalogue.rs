@@ -0,0 +1,444 @@
+use crate::calculator::{CalculationEngine, CalculationError};
+use crate::models::*;
+use rust_decimal::Decimal;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+// One flag per filter in a FilmQuery, in the order they are checked
+const FILTER_TYPE: usize = 0;
+const FILTER_ISO: usize = 1;
+const FILTER_MANUFACTURER: usize = 2;
+const FILTER_PRODUCTION: usize = 3;
+const FILTER_GRAIN: usize = 4;
+const FILTER_CONTRAST: usize = 5;
+const FILTER_USES: usize = 6;
+const FILTER_COUNT: usize = 7;
+
+/// Push/pull levels datasheets publish times for, from pull 2 to push 3
+pub const PUBLISHED_PUSH_PULL: [i32; 6] = [-2, -1, 0, 1, 2, 3];
+
+impl CalculationEngine {
+    /// Films passing every filter of the query, sorted and paged, with facet
+    /// counts that leave out each facet's own filter. `total` counts every
+    /// matching film, whatever the offset and limit.
+    pub fn query_films(&self, query: &FilmQuery) -> Result<FilmQueryResult, CalculationError> {
+        let database = self.get_database()?;
+
+        let evaluated: Vec<(&String, &Film, [bool; FILTER_COUNT])> = database.films
+            .iter()
+            .map(|(key, film)| (key, film, evaluate_filters(film, query)))
+            .collect();
+
+        let facets = build_facets(&evaluated);
+
+        let mut matching: Vec<(&String, &Film)> = evaluated
+            .iter()
+            .filter(|(_, _, passed)| passed.iter().all(|&pass| pass))
+            .map(|(key, film, _)| (*key, *film))
+            .collect();
+
+        matching.sort_by(|(a_key, a), (b_key, b)| {
+            compare_films(a, b, query.sort_by, query.descending)
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| a_key.cmp(b_key))
+        });
+
+        let total = matching.len();
+        let films = matching
+            .into_iter()
+            .skip(query.offset)
+            .take(query.limit.unwrap_or(usize::MAX))
+            .map(|(key, film)| summarize_film(key, film))
+            .collect();
+
+        Ok(FilmQueryResult {
+            total,
+            offset: query.offset,
+            films,
+            facets,
+        })
+    }
+
+    /// Every film with published times for the developer, one entry per dilution.
+    /// A dilution key such as "kodak_hc110_b", or the dilution argument, narrows
+    /// it down to one dilution. Black and white times are converted to 20°C.
+    pub fn get_films_for_developer(&self, developer_key: &str, dilution: Option<&str>) -> Result<Vec<DeveloperFilmEntry>, CalculationError> {
+        let database = self.get_database()?;
+        let developer = self.find_developer(&database.developers, developer_key)?;
+        let standard_temperature = Decimal::from(20);
+
+        // A dilution key such as "kodak_hc110_b" only lists that dilution
+        let is_combination_key = database.films
+            .values()
+            .any(|film| film.developers.contains_key(developer_key));
+
+        let mut entries: Vec<DeveloperFilmEntry> = Vec::new();
+        for (film_key, film) in &database.films {
+            for (combination_key, data) in &film.developers {
+                let same_developer = if is_combination_key {
+                    combination_key == developer_key
+                } else {
+                    self.find_developer(&database.developers, combination_key)
+                        .is_ok_and(|other| std::ptr::eq(other, developer))
+                };
+                if !same_developer {
+                    continue;
+                }
+
+                if let Some(dilution) = dilution {
+                    if !data.dilution.eq_ignore_ascii_case(dilution) && combination_key != dilution {
+                        continue;
+                    }
+                }
+
+                let (temperature, factor) = match film.film_type {
+                    FilmType::BlackWhite => {
+                        let datasheet_temperature = data.temperature_c.unwrap_or(standard_temperature);
+                        let compensation = self.get_temperature_compensation(&database.temperature_compensation, datasheet_temperature);
+                        (standard_temperature, Decimal::ONE / compensation)
+                    },
+                    FilmType::ColorNegative => (data.developer_temp_c.unwrap_or(standard_temperature), Decimal::ONE),
+                    FilmType::Slide => (data.first_dev_temp_c.unwrap_or(standard_temperature), Decimal::ONE),
+                };
+
+                let push_pull_times: Vec<PushPullTime> = PUBLISHED_PUSH_PULL
+                    .into_iter()
+                    .filter_map(|push_pull| {
+                        self.get_datasheet_time(film, data, push_pull).map(|time| PushPullTime {
+                            push_pull,
+                            time_minutes: (time * factor).round_dp(2),
+                        })
+                    })
+                    .collect();
+
+                entries.push(DeveloperFilmEntry {
+                    film: summarize_film(film_key, film),
+                    developer_key: combination_key.clone(),
+                    dilution: data.display_dilution(),
+                    temperature,
+                    time_minutes: push_pull_times
+                        .iter()
+                        .find(|entry| entry.push_pull == 0)
+                        .map(|entry| entry.time_minutes),
+                    push_pull_times,
+                });
+            }
+        }
+
+        entries.sort_by(|a, b| {
+            a.film.name.cmp(&b.film.name)
+                .then_with(|| a.dilution.cmp(&b.dilution))
+                .then_with(|| a.developer_key.cmp(&b.developer_key))
+        });
+
+        Ok(entries)
+    }
+
+    /// Every development time of the films the query matches, for exporting the catalogue
+    pub fn database_summary(&self, query: &FilmQuery) -> Result<DatabaseSummary, CalculationError> {
+        let database = self.get_database()?;
+        let all_matching = FilmQuery {
+            offset: 0,
+            limit: None,
+            ..query.clone()
+        };
+
+        let mut developer_keys: Vec<String> = Vec::new();
+        let mut films = Vec::new();
+        for summary in self.query_films(&all_matching)?.films {
+            let film = &database.films[&summary.key];
+
+            let mut developments = Vec::with_capacity(film.developers.len());
+            for (combination_key, data) in &film.developers {
+                let developer = self.find_developer(&database.developers, combination_key).ok();
+                if let Some(key) = developer.and_then(|developer| developer_key(database, developer)) {
+                    if !developer_keys.contains(key) {
+                        developer_keys.push(key.clone());
+                    }
+                }
+
+                developments.push(CatalogueDevelopment {
+                    developer_key: combination_key.clone(),
+                    developer_name: developer.map(|developer| developer.name.clone()).unwrap_or_else(|| combination_key.clone()),
+                    dilution: data.display_dilution(),
+                    temperature: datasheet_temperature(film, data),
+                    push_pull_times: PUBLISHED_PUSH_PULL
+                        .into_iter()
+                        .filter_map(|push_pull| {
+                            self.get_datasheet_time(film, data, push_pull)
+                                .map(|time_minutes| PushPullTime { push_pull, time_minutes })
+                        })
+                        .collect(),
+                });
+            }
+            developments.sort_by(|a, b| {
+                a.developer_name.cmp(&b.developer_name)
+                    .then_with(|| a.dilution.cmp(&b.dilution))
+                    .then_with(|| a.developer_key.cmp(&b.developer_key))
+            });
+
+            films.push(CatalogueFilm {
+                film: summary,
+                developments,
+            });
+        }
+
+        let mut developers: Vec<CatalogueDeveloper> = developer_keys
+            .into_iter()
+            .map(|key| {
+                let developer = &database.developers[&key];
+                let mut dilutions: Vec<String> = developer.dilutions.values().map(|info| info.ratio.clone()).collect();
+                dilutions.sort();
+                CatalogueDeveloper {
+                    name: developer.name.clone(),
+                    manufacturer: developer.manufacturer.clone(),
+                    developer_type: developer.developer_type.clone(),
+                    dilutions,
+                    stock_life_months: developer.stock_life_months,
+                    working_life_hours: developer.working_life_hours,
+                    key,
+                }
+            })
+            .collect();
+        developers.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.key.cmp(&b.key)));
+
+        Ok(DatabaseSummary {
+            version: database.metadata.version.clone(),
+            films,
+            developers,
+        })
+    }
+}
+
+// The database key of a developer found through find_developer
+fn developer_key<'a>(database: &'a Database, developer: &Developer) -> Option<&'a String> {
+    database.developers
+        .iter()
+        .find(|(_, candidate)| std::ptr::eq(*candidate, developer))
+        .map(|(key, _)| key)
+}
+
+pub fn datasheet_temperature(film: &Film, data: &DeveloperData) -> Decimal {
+    let temperature = match film.film_type {
+        FilmType::BlackWhite => data.temperature_c,
+        FilmType::ColorNegative => data.developer_temp_c,
+        FilmType::Slide => data.first_dev_temp_c,
+    };
+    temperature.unwrap_or(Decimal::from(20))
+}
+
+pub fn push_pull_label(push_pull: i32) -> String {
+    match push_pull {
+        0 => "Normal".to_string(),
+        stops if stops > 0 => format!("Push {}", stops),
+        stops => format!("Pull {}", -stops),
+    }
+}
+
+pub fn summarize_film(key: &str, film: &Film) -> FilmSummary {
+    FilmSummary {
+        key: key.to_string(),
+        name: film.name.clone(),
+        manufacturer: film.manufacturer.clone(),
+        iso: film.iso,
+        film_type: film.film_type.clone(),
+        year_released: film.year_released,
+        current_production: film.current_production,
+        price_35mm_usd: film.price_35mm_usd,
+        grain: film.grain.clone(),
+        contrast: film.contrast.clone(),
+        best_uses: film.best_uses.clone(),
+        developer_count: film.developers.len(),
+    }
+}
+
+fn evaluate_filters(film: &Film, query: &FilmQuery) -> [bool; FILTER_COUNT] {
+    let mut passed = [true; FILTER_COUNT];
+
+    passed[FILTER_TYPE] = query.film_types.is_empty() || query.film_types.contains(&film.film_type);
+    passed[FILTER_ISO] = query.iso_min.is_none_or(|min| film.iso >= min)
+        && query.iso_max.is_none_or(|max| film.iso <= max);
+    passed[FILTER_MANUFACTURER] = matches_any(&query.manufacturers, &film.manufacturer);
+    passed[FILTER_PRODUCTION] = query.current_production.is_none_or(|current| film.current_production == current);
+    passed[FILTER_GRAIN] = matches_any(&query.grain, &film.grain);
+    passed[FILTER_CONTRAST] = matches_any(&query.contrast, &film.contrast);
+    passed[FILTER_USES] = query.best_uses
+        .iter()
+        .all(|wanted| film.best_uses.iter().any(|used| used.eq_ignore_ascii_case(wanted)));
+
+    passed
+}
+
+fn matches_any(wanted: &[String], value: &str) -> bool {
+    wanted.is_empty() || wanted.iter().any(|candidate| candidate.eq_ignore_ascii_case(value))
+}
+
+// Each facet is counted with every filter applied except its own, so the
+// counts show how many films selecting that value would add or leave
+fn build_facets(evaluated: &[(&String, &Film, [bool; FILTER_COUNT])]) -> FilmFacets {
+    let facet_films = |facet: usize| {
+        evaluated
+            .iter()
+            .filter(move |(_, _, passed)| passed.iter().enumerate().all(|(index, &pass)| pass || index == facet))
+            .map(|(_, film, _)| *film)
+    };
+
+    let mut iso = count_values(facet_films(FILTER_ISO).map(|film| film.iso.to_string()));
+    iso.sort_by_key(|facet| facet.value.parse::<u32>().unwrap_or(u32::MAX));
+
+    FilmFacets {
+        film_types: count_values(facet_films(FILTER_TYPE).map(|film| film.film_type.as_str().to_string())),
+        iso,
+        manufacturers: count_values(facet_films(FILTER_MANUFACTURER).map(|film| film.manufacturer.clone())),
+        current_production: count_values(facet_films(FILTER_PRODUCTION).map(|film| film.current_production.to_string())),
+        grain: count_values(facet_films(FILTER_GRAIN).map(|film| film.grain.clone())),
+        contrast: count_values(facet_films(FILTER_CONTRAST).map(|film| film.contrast.clone())),
+        best_uses: count_values(facet_films(FILTER_USES).flat_map(|film| film.best_uses.iter().cloned())),
+    }
+}
+
+fn count_values(values: impl Iterator<Item = String>) -> Vec<FacetCount> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for value in values {
+        *counts.entry(value).or_insert(0) += 1;
+    }
+
+    let mut facets: Vec<FacetCount> = counts
+        .into_iter()
+        .map(|(value, count)| FacetCount { value, count })
+        .collect();
+    facets.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+    facets
+}
+
+fn compare_films(a: &Film, b: &Film, sort_by: FilmSortKey, descending: bool) -> Ordering {
+    match sort_by {
+        FilmSortKey::Name => directed(a.name.to_lowercase().cmp(&b.name.to_lowercase()), descending),
+        FilmSortKey::Iso => directed(a.iso.cmp(&b.iso), descending),
+        FilmSortKey::Manufacturer => directed(a.manufacturer.to_lowercase().cmp(&b.manufacturer.to_lowercase()), descending),
+        FilmSortKey::YearReleased => compare_missing_last(a.year_released, b.year_released, descending),
+        FilmSortKey::Price => compare_missing_last(a.price_35mm_usd, b.price_35mm_usd, descending),
+    }
+}
+
+fn directed(ordering: Ordering, descending: bool) -> Ordering {
+    if descending { ordering.reverse() } else { ordering }
+}
+
+// Films without the value come last in either direction
+fn compare_missing_last<T: Ord>(a: Option<T>, b: Option<T>, descending: bool) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => directed(a.cmp(&b), descending),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::test_support::{database, engine, engine_with};
+
+    fn count(facets: &[FacetCount], value: &str) -> usize {
+        facets.iter().find(|facet| facet.value == value).map_or(0, |facet| facet.count)
+    }
+
+    #[test]
+    fn facets_leave_out_their_own_filter() {
+        let database = database();
+        let engine = engine_with(database.clone());
+        let query = FilmQuery {
+            film_types: vec![FilmType::BlackWhite],
+            manufacturers: vec!["Ilford".to_string()],
+            ..Default::default()
+        };
+        let result = engine.query_films(&query).unwrap();
+
+        let films = || database.films.values();
+        let ilford = |film: &&Film| film.manufacturer == "Ilford";
+        let black_white = |film: &&Film| film.film_type == FilmType::BlackWhite;
+
+        assert_eq!(result.total, films().filter(ilford).filter(black_white).count());
+        // The type facet ignores the type filter but keeps the manufacturer one
+        assert_eq!(count(&result.facets.film_types, "black_white"), result.total);
+        assert_eq!(
+            count(&result.facets.film_types, "color_negative"),
+            films().filter(ilford).filter(|film| film.film_type == FilmType::ColorNegative).count()
+        );
+        // And the manufacturer facet the other way round
+        assert_eq!(count(&result.facets.manufacturers, "Ilford"), result.total);
+        assert_eq!(
+            count(&result.facets.manufacturers, "Kodak"),
+            films().filter(black_white).filter(|film| film.manufacturer == "Kodak").count()
+        );
+        assert!(count(&result.facets.manufacturers, "Kodak") > 0);
+    }
+
+    #[test]
+    fn pages_past_the_end_or_without_room_are_empty() {
+        let engine = engine();
+        let everything = engine.query_films(&FilmQuery::default()).unwrap();
+        let total = everything.total;
+        assert_eq!(everything.films.len(), total);
+
+        let past_end = engine.query_films(&FilmQuery { offset: total + 5, ..Default::default() }).unwrap();
+        assert!(past_end.films.is_empty());
+        assert_eq!(past_end.total, total);
+        assert_eq!(past_end.offset, total + 5);
+
+        let no_room = engine.query_films(&FilmQuery { limit: Some(0), ..Default::default() }).unwrap();
+        assert!(no_room.films.is_empty());
+        assert_eq!(no_room.total, total);
+
+        let last_page = engine.query_films(&FilmQuery { offset: total - 2, limit: Some(10), ..Default::default() }).unwrap();
+        let last_keys: Vec<&str> = last_page.films.iter().map(|film| film.key.as_str()).collect();
+        let expected: Vec<&str> = everything.films[total - 2..].iter().map(|film| film.key.as_str()).collect();
+        assert_eq!(last_keys, expected);
+    }
+
+    #[test]
+    fn films_without_price_or_year_sort_last_in_both_directions() {
+        let mut database = database();
+        let mut keys: Vec<String> = database.films.keys().cloned().collect();
+        keys.sort();
+        for key in &keys[..3] {
+            let film = database.films.get_mut(key).unwrap();
+            film.price_35mm_usd = None;
+            film.year_released = None;
+        }
+        let engine = engine_with(database);
+
+        for sort_by in [FilmSortKey::Price, FilmSortKey::YearReleased] {
+            for descending in [false, true] {
+                let result = engine.query_films(&FilmQuery { sort_by, descending, ..Default::default() }).unwrap();
+                let values: Vec<Option<Decimal>> = result.films
+                    .iter()
+                    .map(|film| match sort_by {
+                        FilmSortKey::Price => film.price_35mm_usd,
+                        _ => film.year_released.map(Decimal::from),
+                    })
+                    .collect();
+
+                let first_missing = values.iter().position(Option::is_none).unwrap();
+                assert!(values[first_missing..].iter().all(Option::is_none), "{:?} descending {}", sort_by, descending);
+                assert_eq!(values.len() - first_missing, 3);
+
+                let present: Vec<Decimal> = values.into_iter().flatten().collect();
+                let ordered = present.windows(2).all(|pair| if descending { pair[0] >= pair[1] } else { pair[0] <= pair[1] });
+                assert!(ordered, "{:?} descending {}", sort_by, descending);
+            }
+        }
+    }
+
+    #[test]
+    fn developer_entries_cover_each_dilution_once() {
+        let engine = engine();
+        let entries = engine.get_films_for_developer("kodak_hc110", None).unwrap();
+        let dilution_b = engine.get_films_for_developer("kodak_hc110_b", None).unwrap();
+        assert!(!dilution_b.is_empty());
+        assert!(dilution_b.len() < entries.len());
+        assert!(dilution_b.iter().all(|entry| entry.developer_key == "kodak_hc110_b"));
+        assert!(entries.iter().all(|entry| entry.developer_key.starts_with("kodak_hc110")));
+    }
+}
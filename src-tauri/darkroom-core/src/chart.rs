@@ -0,0 +1,132 @@
+use crate::calculator::{CalculationEngine, CalculationError};
+use crate::catalogue::{datasheet_temperature, summarize_film, PUBLISHED_PUSH_PULL};
+#[cfg(feature = "export")]
+use crate::export::{ExportError, ExportManager};
+#[cfg(feature = "export")]
+use crate::markup::development_charts_html;
+use crate::models::*;
+#[cfg(feature = "export")]
+use crate::pdf::render_development_charts;
+#[cfg(feature = "export")]
+use chrono::Utc;
+use rust_decimal::Decimal;
+
+// Temperatures in the compensation table of a chart, in whole degrees
+const CHART_TEMPERATURES: [i64; 7] = [18, 19, 20, 21, 22, 23, 24];
+// Any valid volume will do, the chart only shows times
+const CHART_VOLUME: u32 = 500;
+
+impl CalculationEngine {
+    /// Every developer and dilution in the database for one film, with a time per
+    /// push/pull level and, for black and white film, per temperature
+    pub fn development_chart(&self, film_key: &str) -> Result<DevelopmentChart, CalculationError> {
+        let database = self.get_database()?;
+        let film = self.get_film_info(film_key)?;
+        let temperatures: Vec<Decimal> = match film.film_type {
+            FilmType::BlackWhite => CHART_TEMPERATURES.iter().map(|&temperature| Decimal::from(temperature)).collect(),
+            // Color processes run at a fixed temperature, there is nothing to compensate
+            FilmType::ColorNegative | FilmType::Slide => Vec::new(),
+        };
+
+        let mut rows = Vec::with_capacity(film.developers.len());
+        for (combination_key, data) in &film.developers {
+            let developer = self.find_developer(&database.developers, combination_key).ok();
+            let temperature = datasheet_temperature(film, data);
+
+            let mut push_pull = Vec::with_capacity(PUBLISHED_PUSH_PULL.len());
+            for level in PUBLISHED_PUSH_PULL {
+                let cell = match self.get_datasheet_time(film, data, level) {
+                    None if !extrapolates(&film.film_type, level) => ChartCell {
+                        time_minutes: None,
+                        derived: false,
+                    },
+                    Some(time) => ChartCell {
+                        time_minutes: Some(time),
+                        derived: false,
+                    },
+                    None => ChartCell {
+                        time_minutes: Some(self.get_base_time(film, data, level)?.round_dp(2)),
+                        derived: true,
+                    },
+                };
+                push_pull.push(cell);
+            }
+
+            let published = self.get_datasheet_time(film, data, 0);
+            let temperature_cells = temperatures
+                .iter()
+                .map(|&chart_temperature| {
+                    let time = self
+                        .calculate_development(CalculationRequest {
+                            film_key: film_key.to_string(),
+                            developer_key: combination_key.clone(),
+                            temperature: chart_temperature,
+                            push_pull: 0,
+                            volume: CHART_VOLUME,
+                        })
+                        .ok()
+                        .map(|result| result.time_minutes.round_dp(2));
+                    ChartCell {
+                        derived: time.is_some() && !(chart_temperature == temperature && time == published),
+                        time_minutes: time,
+                    }
+                })
+                .collect();
+
+            rows.push(ChartRow {
+                developer_key: combination_key.clone(),
+                developer_name: developer.map(|developer| developer.name.clone()).unwrap_or_else(|| combination_key.clone()),
+                dilution: data.display_dilution(),
+                temperature,
+                push_pull,
+                temperatures: temperature_cells,
+            });
+        }
+        rows.sort_by(|a, b| {
+            a.developer_name.cmp(&b.developer_name)
+                .then_with(|| a.dilution.cmp(&b.dilution))
+                .then_with(|| a.developer_key.cmp(&b.developer_key))
+        });
+
+        Ok(DevelopmentChart {
+            film: summarize_film(film_key, film),
+            push_pull_values: PUBLISHED_PUSH_PULL.to_vec(),
+            temperatures,
+            rows,
+        })
+    }
+}
+
+#[cfg(feature = "export")]
+impl ExportManager {
+    /// One page per chart, for pinning up next to the sink
+    pub fn export_development_charts(
+        &self,
+        charts: &[DevelopmentChart],
+        format: ExportFormat,
+        file_path: Option<String>,
+    ) -> Result<String, ExportError> {
+        let timestamp = Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string();
+        let content = match &format {
+            ExportFormat::Pdf => render_development_charts(charts, &timestamp)?,
+            ExportFormat::Html => development_charts_html(charts, &timestamp).into_bytes(),
+            _ => return Err(ExportError::UnsupportedFormat(format)),
+        };
+
+        let name = match charts {
+            [chart] => format!("{} development chart", chart.film.name),
+            _ => "Development charts".to_string(),
+        };
+        let path = self.write_file(file_path.as_deref(), &[&name], format.extension(), &content)?;
+        Ok(format!("Exported {} development chart(s) to: {}", charts.len(), path))
+    }
+}
+
+// Whether get_base_time has a rule for this level when the datasheet has no time.
+// Color processes are not extrapolated past pull 1 and push 2.
+fn extrapolates(film_type: &FilmType, push_pull: i32) -> bool {
+    match film_type {
+        FilmType::BlackWhite => true,
+        FilmType::ColorNegative | FilmType::Slide => (-1..=2).contains(&push_pull),
+    }
+}
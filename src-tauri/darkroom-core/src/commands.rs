@@ -0,0 +1,664 @@
+// The desktop app's Tauri commands. They return errors as strings, which is
+// what the frontend receives.
+use crate::calculator::CalculationEngine;
+use crate::export::ExportManager;
+use crate::export_files::ExportDirectories;
+use crate::import::parse_export;
+use crate::models::*;
+use crate::scheduler::SessionScheduler;
+use crate::template::TemplateLibrary;
+use crate::timer::TimerService;
+use crate::database::DatabaseManager;
+use std::sync::Mutex;
+use tauri::{Manager, State};
+use tauri_plugin_dialog::DialogExt;
+
+/// Global state for the calculation engine
+pub type CalculationEngineState = Mutex<CalculationEngine>;
+
+// Learn more about Tauri commands at https://tauri.app/v1/guides/features/command
+#[tauri::command]
+pub fn greet(name: &str) -> String {
+    format!("Hello, {}! You've been greeted from Rust!", name)
+}
+
+/// Command to load the film database
+#[tauri::command]
+pub async fn load_database(
+    engine_state: State<'_, CalculationEngineState>,
+    app_handle: tauri::AppHandle,
+) -> Result<serde_json::Value, String> {
+    let mut db_manager = DatabaseManager::new();
+    
+    // Try to load from the bundled database file
+    let resource_path = app_handle
+        .path()
+        .resolve("complete_database.json", tauri::path::BaseDirectory::Resource)
+        .map_err(|e| format!("Failed to resolve resource path: {}", e))?;
+    
+    db_manager.load_from_file(&resource_path)
+        .map_err(|e| format!("Failed to load database: {}", e))?;
+    
+    let database = db_manager.take_database()
+        .ok_or("Failed to extract database")?;
+    
+    let stats = database.metadata.clone();
+    
+    // Load into calculation engine
+    let mut engine = engine_state.lock().unwrap();
+    engine.load_database(database);
+    
+    Ok(serde_json::json!({
+        "status": "success",
+        "message": "Database loaded successfully",
+        "stats": {
+            "film_count": stats.film_count,
+            "developer_count": stats.developer_count,
+            "total_combinations": stats.total_combinations,
+            "version": stats.version,
+            "last_updated": stats.last_updated
+        }
+    }))
+}
+
+/// Command to get all available films
+#[tauri::command]
+pub async fn get_films(
+    engine_state: State<'_, CalculationEngineState>,
+) -> Result<Vec<Film>, String> {
+    let engine = engine_state.lock().unwrap();
+    let films = engine.get_available_films()
+        .map_err(|e| format!("Failed to get films: {}", e))?;
+    
+    Ok(films.into_iter().cloned().collect())
+}
+
+/// Command to filter, sort and page through films with facet counts
+#[tauri::command]
+pub async fn query_films(
+    query: FilmQuery,
+    engine_state: State<'_, CalculationEngineState>,
+) -> Result<FilmQueryResult, String> {
+    let engine = engine_state.lock().unwrap();
+    engine.query_films(&query)
+        .map_err(|e| format!("Failed to query films: {}", e))
+}
+
+/// Command to get available developers for a specific film
+#[tauri::command]
+pub async fn get_developers_for_film(
+    film_key: String,
+    engine_state: State<'_, CalculationEngineState>,
+) -> Result<Vec<String>, String> {
+    let engine = engine_state.lock().unwrap();
+    engine.get_available_developers_for_film(&film_key)
+        .map_err(|e| format!("Failed to get developers: {}", e))
+}
+
+/// Command to list every film with data for a developer, optionally one dilution
+#[tauri::command]
+pub async fn get_films_for_developer(
+    developer_key: String,
+    dilution: Option<String>,
+    engine_state: State<'_, CalculationEngineState>,
+) -> Result<Vec<DeveloperFilmEntry>, String> {
+    let engine = engine_state.lock().unwrap();
+    engine.get_films_for_developer(&developer_key, dilution.as_deref())
+        .map_err(|e| format!("Failed to get films: {}", e))
+}
+
+/// Command to get film information
+#[tauri::command]
+pub async fn get_film_info(
+    film_key: String,
+    engine_state: State<'_, CalculationEngineState>,
+) -> Result<Film, String> {
+    let engine = engine_state.lock().unwrap();
+    let film = engine.get_film_info(&film_key)
+        .map_err(|e| format!("Failed to get film info: {}", e))?;
+    
+    Ok(film.clone())
+}
+
+/// Command to get developer information
+#[tauri::command]
+pub async fn get_developer_info(
+    developer_key: String,
+    engine_state: State<'_, CalculationEngineState>,
+) -> Result<Developer, String> {
+    let engine = engine_state.lock().unwrap();
+    let developer = engine.get_developer_info(&developer_key)
+        .map_err(|e| format!("Failed to get developer info: {}", e))?;
+    
+    Ok(developer.clone())
+}
+
+/// Command to search films and developers by name, alias, manufacturer or ISO
+#[tauri::command]
+pub async fn search_catalogue(
+    query: String,
+    limit: Option<usize>,
+    engine_state: State<'_, CalculationEngineState>,
+) -> Result<Vec<SearchResult>, String> {
+    let engine = engine_state.lock().unwrap();
+    engine.search_catalogue(&query, limit.unwrap_or(20))
+        .map_err(|e| format!("Search failed: {}", e))
+}
+
+/// Command to rank the developers with data for a film against the user's goals
+#[tauri::command]
+pub async fn recommend_developers(
+    request: RecommendationRequest,
+    engine_state: State<'_, CalculationEngineState>,
+) -> Result<Vec<DeveloperRecommendation>, String> {
+    let engine = engine_state.lock().unwrap();
+    engine.recommend_developers(&request)
+        .map_err(|e| format!("Recommendation failed: {}", e))
+}
+
+/// Command to suggest current films to replace a discontinued one in an existing recipe
+#[tauri::command]
+pub async fn find_substitutes(
+    recipe: CalculationRequest,
+    limit: Option<usize>,
+    engine_state: State<'_, CalculationEngineState>,
+) -> Result<SubstitutionResult, String> {
+    let engine = engine_state.lock().unwrap();
+    engine.find_substitutes(&recipe, limit)
+        .map_err(|e| format!("Failed to find substitutes: {}", e))
+}
+
+/// Command to calculate development parameters
+#[tauri::command]
+pub async fn calculate_development(
+    request: CalculationRequest,
+    engine_state: State<'_, CalculationEngineState>,
+) -> Result<CalculationResult, String> {
+    let engine = engine_state.lock().unwrap();
+    engine.calculate_development(request)
+        .map_err(|e| format!("Calculation failed: {}", e))
+}
+
+/// Command to compare developers, temperatures and push/pull values for one film
+#[tauri::command]
+pub async fn calculate_comparison(
+    request: ComparisonRequest,
+    engine_state: State<'_, CalculationEngineState>,
+) -> Result<ComparisonMatrix, String> {
+    let engine = engine_state.lock().unwrap();
+    engine.calculate_matrix(&request)
+        .map_err(|e| format!("Comparison failed: {}", e))
+}
+
+/// Command to group a session's rolls into tanks that can share chemistry
+#[tauri::command]
+pub async fn plan_session(
+    request: SessionRequest,
+    engine_state: State<'_, CalculationEngineState>,
+) -> Result<SessionPlan, String> {
+    let engine = engine_state.lock().unwrap();
+    engine.plan_session(&request)
+        .map_err(|e| format!("Session planning failed: {}", e))
+}
+
+/// Command to build the step-by-step process for one tank
+#[tauri::command]
+pub async fn build_process_plan(
+    request: ProcessPlanRequest,
+    engine_state: State<'_, CalculationEngineState>,
+) -> Result<ProcessPlan, String> {
+    let engine = engine_state.lock().unwrap();
+    engine.build_process_plan(&request)
+        .map_err(|e| format!("Process plan failed: {}", e))
+}
+
+/// Command to stagger several tanks into one conflict-free session timeline
+#[tauri::command]
+pub async fn schedule_session(request: ScheduleRequest) -> Result<SessionSchedule, String> {
+    let scheduler = SessionScheduler::new(request.min_pour_gap_seconds);
+    Ok(scheduler.schedule(&request.plans))
+}
+
+/// Command to start a named process timer, progress is sent as process-timer events
+#[tauri::command]
+pub async fn start_process_timer(
+    request: TimerRequest,
+    timer_service: State<'_, TimerService>,
+) -> Result<TimerStatus, String> {
+    timer_service.start(request)
+        .map_err(|e| format!("Failed to start timer: {}", e))
+}
+
+#[tauri::command]
+pub async fn pause_process_timer(name: String, timer_service: State<'_, TimerService>) -> Result<TimerStatus, String> {
+    timer_service.pause(&name)
+        .map_err(|e| format!("Failed to pause timer: {}", e))
+}
+
+#[tauri::command]
+pub async fn resume_process_timer(name: String, timer_service: State<'_, TimerService>) -> Result<TimerStatus, String> {
+    timer_service.resume(&name)
+        .map_err(|e| format!("Failed to resume timer: {}", e))
+}
+
+#[tauri::command]
+pub async fn skip_timer_phase(name: String, timer_service: State<'_, TimerService>) -> Result<TimerStatus, String> {
+    timer_service.skip(&name)
+        .map_err(|e| format!("Failed to skip phase: {}", e))
+}
+
+/// Command to add or remove seconds from a timer's current phase
+#[tauri::command]
+pub async fn adjust_timer_phase(
+    name: String,
+    delta_seconds: i32,
+    timer_service: State<'_, TimerService>,
+) -> Result<TimerStatus, String> {
+    timer_service.adjust(&name, delta_seconds)
+        .map_err(|e| format!("Failed to adjust timer: {}", e))
+}
+
+#[tauri::command]
+pub async fn cancel_process_timer(name: String, timer_service: State<'_, TimerService>) -> Result<TimerStatus, String> {
+    timer_service.cancel(&name)
+        .map_err(|e| format!("Failed to cancel timer: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_timer_status(name: String, timer_service: State<'_, TimerService>) -> Result<Option<TimerStatus>, String> {
+    Ok(timer_service.status(&name))
+}
+
+#[tauri::command]
+pub async fn get_timer_statuses(timer_service: State<'_, TimerService>) -> Result<Vec<TimerStatus>, String> {
+    Ok(timer_service.statuses())
+}
+
+/// Command to get the next actions due across all timers, soonest first
+#[tauri::command]
+pub async fn get_upcoming_actions(
+    limit: Option<usize>,
+    timer_service: State<'_, TimerService>,
+) -> Result<Vec<UpcomingAction>, String> {
+    Ok(timer_service.upcoming(limit.unwrap_or(10)))
+}
+
+/// Command to list timers still running, including ones restored after a restart or crash
+#[tauri::command]
+pub async fn list_in_flight_timers(timer_service: State<'_, TimerService>) -> Result<Vec<InFlightTimer>, String> {
+    timer_service.in_flight()
+        .map_err(|e| format!("Failed to list timers: {}", e))
+}
+
+#[tauri::command]
+pub async fn reattach_process_timer(name: String, timer_service: State<'_, TimerService>) -> Result<InFlightTimer, String> {
+    timer_service.reattach(&name)
+        .map_err(|e| format!("Failed to reattach timer: {}", e))
+}
+
+/// Command to save user preferences
+#[tauri::command]
+pub async fn save_preferences(preferences: serde_json::Value) -> Result<String, String> {
+    // TODO: Implement local storage for user preferences
+    println!("Saving preferences: {}", preferences);
+    Ok("Preferences saved successfully".to_string())
+}
+
+/// Command to export calculation results
+#[tauri::command]
+pub async fn export_calculation(
+    calculation: CalculationResult,
+    request: Option<CalculationRequest>,
+    format: ExportFormat,
+    file_path: Option<String>,
+    overwrite: Option<bool>,
+    templates: State<'_, TemplateLibrary>,
+    directories: State<'_, ExportDirectories>,
+) -> Result<String, String> {
+    let export_manager = ExportManager::new()
+        .with_templates(templates.inner().clone())
+        .with_files(directories.files(overwrite.unwrap_or(false)));
+    export_manager.export_calculation(&calculation, request.as_ref(), format, file_path)
+        .map_err(|e| format!("Export failed: {}", e))
+}
+
+/// Command to read back a JSON export from an approved export folder, recalculated against the current database
+#[tauri::command]
+pub async fn import_recipe(
+    file_path: String,
+    engine_state: State<'_, CalculationEngineState>,
+    directories: State<'_, ExportDirectories>,
+) -> Result<ImportedRecipe, String> {
+    let content = directories.files(false).read(&file_path)
+        .map_err(|e| format!("Import failed: {}", e))?;
+    let data = parse_export(&content)
+        .map_err(|e| format!("Import failed: {}", e))?;
+
+    let engine = engine_state.lock().unwrap();
+    engine.recalculate_export(data)
+        .map_err(|e| format!("Import failed: {}", e))
+}
+
+/// Command to turn a recipe into a short code that can be pasted or typed in
+#[tauri::command]
+pub async fn create_share_code(
+    request: CalculationRequest,
+    engine_state: State<'_, CalculationEngineState>,
+) -> Result<ShareCode, String> {
+    let engine = engine_state.lock().unwrap();
+    engine.share_code(&request)
+        .map_err(|e| format!("Share code failed: {}", e))
+}
+
+/// Command to read a share code back and calculate the recipe
+#[tauri::command]
+pub async fn load_share_code(
+    code: String,
+    engine_state: State<'_, CalculationEngineState>,
+) -> Result<SharedRecipe, String> {
+    let engine = engine_state.lock().unwrap();
+    engine.load_share_code(&code)
+        .map_err(|e| format!("Invalid share code: {}", e))
+}
+
+/// Command to export a recipe's share code as a QR code, generated offline
+#[tauri::command]
+pub async fn export_share_qr(
+    request: CalculationRequest,
+    format: QrFormat,
+    file_path: Option<String>,
+    overwrite: Option<bool>,
+    engine_state: State<'_, CalculationEngineState>,
+    directories: State<'_, ExportDirectories>,
+) -> Result<String, String> {
+    let share = {
+        let engine = engine_state.lock().unwrap();
+        engine.share_code(&request)
+            .map_err(|e| format!("Share code failed: {}", e))?
+    };
+
+    let export_manager = ExportManager::new().with_files(directories.files(overwrite.unwrap_or(false)));
+    export_manager.export_share_qr(&share, format, file_path)
+        .map_err(|e| format!("QR code export failed: {}", e))
+}
+
+/// Command to list the folders exports may be written to, the default one first
+#[tauri::command]
+pub async fn list_export_directories(directories: State<'_, ExportDirectories>) -> Result<Vec<String>, String> {
+    Ok(directories.list().iter().map(|directory| directory.to_string_lossy().to_string()).collect())
+}
+
+/// Command to ask the user for a folder in a native dialog and approve it for exports.
+/// The folder never comes from the frontend, so only the user can widen where files go.
+#[tauri::command]
+pub async fn approve_export_directory(
+    app_handle: tauri::AppHandle,
+    directories: State<'_, ExportDirectories>,
+) -> Result<Vec<String>, String> {
+    let picked = app_handle.dialog().file()
+        .set_title("Choose a folder for exports")
+        .blocking_pick_folder();
+
+    // Cancelling the dialog leaves the approved folders as they were
+    let approved = match picked {
+        Some(folder) => {
+            let directory = folder.into_path()
+                .map_err(|e| format!("Failed to approve export folder: {}", e))?;
+            directories.approve(&directory)
+                .map_err(|e| format!("Failed to approve export folder: {}", e))?
+        },
+        None => directories.list(),
+    };
+    Ok(approved.iter().map(|directory| directory.to_string_lossy().to_string()).collect())
+}
+
+/// Command to stop allowing exports to a folder
+#[tauri::command]
+pub async fn revoke_export_directory(
+    directory: String,
+    directories: State<'_, ExportDirectories>,
+) -> Result<Vec<String>, String> {
+    let approved = directories.revoke(&directory)
+        .map_err(|e| format!("Failed to remove export folder: {}", e))?;
+    Ok(approved.iter().map(|directory| directory.to_string_lossy().to_string()).collect())
+}
+
+/// Command to list the user templates that ExportFormat::Template can use
+#[tauri::command]
+pub async fn list_export_templates(templates: State<'_, TemplateLibrary>) -> Result<Vec<ExportTemplate>, String> {
+    templates.list()
+        .map_err(|e| format!("Failed to list templates: {}", e))
+}
+
+/// Command to get the templates folder, creating it so users can open it and drop files in
+#[tauri::command]
+pub async fn get_templates_folder(templates: State<'_, TemplateLibrary>) -> Result<String, String> {
+    std::fs::create_dir_all(templates.directory())
+        .map_err(|e| format!("Failed to create templates folder: {}", e))?;
+    Ok(templates.directory().to_string_lossy().to_string())
+}
+
+/// Command to export the catalogue with every development time, filtered like the film list
+#[tauri::command]
+pub async fn export_database_summary(
+    query: Option<FilmQuery>,
+    format: ExportFormat,
+    csv_options: Option<CsvOptions>,
+    file_path: Option<String>,
+    overwrite: Option<bool>,
+    engine_state: State<'_, CalculationEngineState>,
+    directories: State<'_, ExportDirectories>,
+) -> Result<String, String> {
+    let summary = {
+        let engine = engine_state.lock().unwrap();
+        engine.database_summary(&query.unwrap_or_default())
+            .map_err(|e| format!("Database summary failed: {}", e))?
+    };
+
+    let export_manager = ExportManager::new().with_files(directories.files(overwrite.unwrap_or(false)));
+    export_manager.export_database_summary(&summary, format, &csv_options.unwrap_or_default(), file_path)
+        .map_err(|e| format!("Database summary export failed: {}", e))
+}
+
+/// Command to export many calculations as one CSV table, one row each
+#[tauri::command]
+pub async fn export_calculations_csv(
+    calculations: Vec<CalculationResult>,
+    options: Option<CsvOptions>,
+    file_path: Option<String>,
+    overwrite: Option<bool>,
+    directories: State<'_, ExportDirectories>,
+) -> Result<String, String> {
+    let export_manager = ExportManager::new().with_files(directories.files(overwrite.unwrap_or(false)));
+    export_manager.export_calculations_csv(&calculations, &options.unwrap_or_default(), file_path)
+        .map_err(|e| format!("CSV export failed: {}", e))
+}
+
+/// Command to export a comparison matrix as CSV, one row per cell
+#[tauri::command]
+pub async fn export_comparison_csv(
+    matrix: ComparisonMatrix,
+    options: Option<CsvOptions>,
+    file_path: Option<String>,
+    overwrite: Option<bool>,
+    directories: State<'_, ExportDirectories>,
+) -> Result<String, String> {
+    let export_manager = ExportManager::new().with_files(directories.files(overwrite.unwrap_or(false)));
+    export_manager.export_comparison_csv(&matrix, &options.unwrap_or_default(), file_path)
+        .map_err(|e| format!("CSV export failed: {}", e))
+}
+
+/// Command to export a process plan as subtitle cues, so a media player can run it
+#[tauri::command]
+pub async fn export_cues(
+    plan: ProcessPlan,
+    format: CueFormat,
+    file_path: Option<String>,
+    overwrite: Option<bool>,
+    directories: State<'_, ExportDirectories>,
+) -> Result<String, String> {
+    let export_manager = ExportManager::new().with_files(directories.files(overwrite.unwrap_or(false)));
+    export_manager.export_cues(&plan, format, file_path)
+        .map_err(|e| format!("Cue export failed: {}", e))
+}
+
+/// Command to put a planned session or process plan, and chemistry expiry dates, on a calendar
+#[tauri::command]
+pub async fn export_calendar(
+    request: CalendarRequest,
+    file_path: Option<String>,
+    overwrite: Option<bool>,
+    engine_state: State<'_, CalculationEngineState>,
+    directories: State<'_, ExportDirectories>,
+) -> Result<String, String> {
+    let expiries = {
+        let engine = engine_state.lock().unwrap();
+        engine.batch_expiries(&request.batches)
+            .map_err(|e| format!("Batch expiry failed: {}", e))?
+    };
+
+    let export_manager = ExportManager::new().with_files(directories.files(overwrite.unwrap_or(false)));
+    export_manager.export_calendar(&request, &expiries, file_path)
+        .map_err(|e| format!("Calendar export failed: {}", e))
+}
+
+/// Command to render a process plan as an audio file of cue tones
+#[tauri::command]
+pub async fn export_audio(
+    plan: ProcessPlan,
+    format: AudioFormat,
+    file_path: Option<String>,
+    overwrite: Option<bool>,
+    directories: State<'_, ExportDirectories>,
+) -> Result<String, String> {
+    let export_manager = ExportManager::new().with_files(directories.files(overwrite.unwrap_or(false)));
+    export_manager.export_audio(&plan, format, file_path)
+        .map_err(|e| format!("Audio export failed: {}", e))
+}
+
+/// Command to export the full recipe, process steps included, in any export format
+#[tauri::command]
+pub async fn export_recipe(
+    request: RecipeCardRequest,
+    format: ExportFormat,
+    file_path: Option<String>,
+    overwrite: Option<bool>,
+    engine_state: State<'_, CalculationEngineState>,
+    templates: State<'_, TemplateLibrary>,
+    directories: State<'_, ExportDirectories>,
+) -> Result<String, String> {
+    let card = {
+        let engine = engine_state.lock().unwrap();
+        engine.build_recipe_card(&request.recipe)
+            .map_err(|e| format!("Recipe card failed: {}", e))?
+    };
+
+    let export_manager = ExportManager::new()
+        .with_templates(templates.inner().clone())
+        .with_files(directories.files(overwrite.unwrap_or(false)));
+    export_manager.export_recipe(&card, &request.recipe.calculation, format, request.page_size, file_path)
+        .map_err(|e| format!("Recipe export failed: {}", e))
+}
+
+/// Command to print a laminated-style recipe card with every step and safety warnings
+#[tauri::command]
+pub async fn export_recipe_card(
+    request: RecipeCardRequest,
+    file_path: Option<String>,
+    overwrite: Option<bool>,
+    engine_state: State<'_, CalculationEngineState>,
+    directories: State<'_, ExportDirectories>,
+) -> Result<String, String> {
+    let card = {
+        let engine = engine_state.lock().unwrap();
+        engine.build_recipe_card(&request.recipe)
+            .map_err(|e| format!("Recipe card failed: {}", e))?
+    };
+
+    let export_manager = ExportManager::new().with_files(directories.files(overwrite.unwrap_or(false)));
+    export_manager.export_recipe_card(&card, request.page_size, file_path)
+        .map_err(|e| format!("Recipe card export failed: {}", e))
+}
+
+/// Command to print a one-page development chart per film for the darkroom wall
+#[tauri::command]
+pub async fn export_development_charts(
+    film_keys: Vec<String>,
+    format: ExportFormat,
+    file_path: Option<String>,
+    overwrite: Option<bool>,
+    engine_state: State<'_, CalculationEngineState>,
+    directories: State<'_, ExportDirectories>,
+) -> Result<String, String> {
+    if film_keys.is_empty() {
+        return Err("Development chart failed: no films selected".to_string());
+    }
+
+    let charts = {
+        let engine = engine_state.lock().unwrap();
+        film_keys
+            .iter()
+            .map(|film_key| engine.development_chart(film_key))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Development chart failed: {}", e))?
+    };
+
+    let export_manager = ExportManager::new().with_files(directories.files(overwrite.unwrap_or(false)));
+    export_manager.export_development_charts(&charts, format, file_path)
+        .map_err(|e| format!("Development chart export failed: {}", e))
+}
+
+/// Every command above, for `tauri::Builder::invoke_handler`. The app has to
+/// manage a [`CalculationEngineState`], a [`TemplateLibrary`], an
+/// [`ExportDirectories`] and a [`TimerService`] for them.
+pub fn invoke_handler() -> impl Fn(tauri::ipc::Invoke) -> bool + Send + Sync + 'static {
+    tauri::generate_handler![
+        greet,
+        load_database,
+        get_films,
+        query_films,
+        get_developers_for_film,
+        get_films_for_developer,
+        get_film_info,
+        get_developer_info,
+        search_catalogue,
+        recommend_developers,
+        find_substitutes,
+        calculate_development,
+        calculate_comparison,
+        plan_session,
+        build_process_plan,
+        schedule_session,
+        start_process_timer,
+        pause_process_timer,
+        resume_process_timer,
+        skip_timer_phase,
+        adjust_timer_phase,
+        cancel_process_timer,
+        get_timer_status,
+        get_timer_statuses,
+        get_upcoming_actions,
+        list_in_flight_timers,
+        reattach_process_timer,
+        save_preferences,
+        export_calculation,
+        import_recipe,
+        create_share_code,
+        load_share_code,
+        export_share_qr,
+        list_export_directories,
+        approve_export_directory,
+        revoke_export_directory,
+        list_export_templates,
+        get_templates_folder,
+        export_database_summary,
+        export_calculations_csv,
+        export_comparison_csv,
+        export_cues,
+        export_calendar,
+        export_audio,
+        export_recipe_card,
+        export_recipe,
+        export_development_charts
+    ]
+}
@@ -0,0 +1,231 @@
+use crate::catalogue::{push_pull_label, PUBLISHED_PUSH_PULL};
+use crate::export::{ExportError, ExportManager};
+use crate::models::*;
+use rust_decimal::Decimal;
+
+const DEFAULT_DELIMITER: char = ',';
+const DEFAULT_DECIMAL_SEPARATOR: char = '.';
+
+const CALCULATION_HEADERS: [&str; 12] = [
+    "Film",
+    "Film Type",
+    "Developer",
+    "Dilution",
+    "Temperature (C)",
+    "Push/Pull (stops)",
+    "Development Time",
+    "Development Time (minutes)",
+    "Developer Amount (ml)",
+    "Water Amount (ml)",
+    "Total Volume (ml)",
+    "Notes",
+];
+
+impl ExportManager {
+    /// One row per calculation, e.g. a whole session or the calculation history
+    pub fn export_calculations_csv(
+        &self,
+        calculations: &[CalculationResult],
+        options: &CsvOptions,
+        file_path: Option<String>,
+    ) -> Result<String, ExportError> {
+        let content = calculations_csv(calculations, options)?;
+        self.write_csv(content, "DarkroomPro calculations", file_path)
+    }
+
+    /// One row per cell, with the error in place of the results for cells that failed
+    pub fn export_comparison_csv(
+        &self,
+        matrix: &ComparisonMatrix,
+        options: &CsvOptions,
+        file_path: Option<String>,
+    ) -> Result<String, ExportError> {
+        let mut writer = CsvWriter::new(options)?;
+        let mut headers = vec!["Developer Key", "Requested Temperature (C)", "Requested Push/Pull (stops)"];
+        headers.extend(CALCULATION_HEADERS);
+        headers.push("Error");
+        writer.row(&headers);
+
+        for cell in &matrix.cells {
+            let mut fields = vec![
+                cell.developer_key.clone(),
+                writer.decimal(cell.temperature),
+                cell.push_pull.to_string(),
+            ];
+            match &cell.result {
+                Some(result) => fields.extend(writer.calculation_fields(result)),
+                None => fields.extend(CALCULATION_HEADERS.iter().map(|_| String::new())),
+            }
+            fields.push(cell.error.as_ref().map(|error| error.message.clone()).unwrap_or_default());
+            writer.row(&fields);
+        }
+
+        self.write_csv(writer.finish(), "DarkroomPro comparison", file_path)
+    }
+
+    fn write_csv(&self, content: String, name: &str, file_path: Option<String>) -> Result<String, ExportError> {
+        let path = self.write_file(file_path.as_deref(), &[name], "csv", content.as_bytes())?;
+        Ok(format!("Exported to CSV file: {}", path))
+    }
+}
+
+pub fn calculations_csv(calculations: &[CalculationResult], options: &CsvOptions) -> Result<String, ExportError> {
+    let mut writer = CsvWriter::new(options)?;
+    writer.row(&CALCULATION_HEADERS);
+    for calculation in calculations {
+        let fields = writer.calculation_fields(calculation);
+        writer.row(&fields);
+    }
+    Ok(writer.finish())
+}
+
+/// One row per film and developer combination, with a minutes column per push/pull level
+pub fn database_summary_csv(summary: &DatabaseSummary, options: &CsvOptions) -> Result<String, ExportError> {
+    let mut writer = CsvWriter::new(options)?;
+    let mut headers: Vec<String> = [
+        "Film Key",
+        "Film",
+        "Manufacturer",
+        "ISO",
+        "Film Type",
+        "Current Production",
+        "Developer Key",
+        "Developer",
+        "Dilution",
+        "Temperature (C)",
+    ]
+    .iter()
+    .map(|header| header.to_string())
+    .collect();
+    headers.extend(PUBLISHED_PUSH_PULL.iter().map(|&push_pull| format!("{} (minutes)", push_pull_label(push_pull))));
+    writer.row(&headers);
+
+    for entry in &summary.films {
+        let film = &entry.film;
+        let film_fields = vec![
+            film.key.clone(),
+            film.name.clone(),
+            film.manufacturer.clone(),
+            film.iso.to_string(),
+            film.film_type.label().to_string(),
+            if film.current_production { "Yes" } else { "No" }.to_string(),
+        ];
+
+        if entry.developments.is_empty() {
+            // Blank developer columns, so the row still lines up with the header
+            let mut fields = film_fields.clone();
+            fields.resize(headers.len(), String::new());
+            writer.row(&fields);
+        }
+        for development in &entry.developments {
+            let mut fields = film_fields.clone();
+            fields.push(development.developer_key.clone());
+            fields.push(development.developer_name.clone());
+            fields.push(development.dilution.clone());
+            fields.push(writer.decimal(development.temperature));
+            for push_pull in PUBLISHED_PUSH_PULL {
+                let time = development.push_pull_times.iter().find(|time| time.push_pull == push_pull);
+                fields.push(time.map(|time| writer.decimal(time.time_minutes)).unwrap_or_default());
+            }
+            writer.row(&fields);
+        }
+    }
+
+    Ok(writer.finish())
+}
+
+// Writes records as described in RFC 4180: CRLF line endings, and fields holding
+// the delimiter, a quote or a line break wrapped in quotes with quotes doubled
+struct CsvWriter {
+    delimiter: char,
+    decimal_separator: char,
+    content: String,
+}
+
+impl CsvWriter {
+    fn new(options: &CsvOptions) -> Result<Self, ExportError> {
+        let delimiter = options.delimiter.unwrap_or(DEFAULT_DELIMITER);
+        let decimal_separator = options.decimal_separator.unwrap_or(DEFAULT_DECIMAL_SEPARATOR);
+
+        if matches!(delimiter, '"' | '\r' | '\n') {
+            return Err(ExportError::InvalidCsvOptions(format!("{:?} cannot be used as the delimiter", delimiter)));
+        }
+        if decimal_separator == delimiter {
+            return Err(ExportError::InvalidCsvOptions(
+                "The decimal separator must differ from the delimiter".to_string(),
+            ));
+        }
+
+        Ok(CsvWriter {
+            delimiter,
+            decimal_separator,
+            content: String::new(),
+        })
+    }
+
+    fn row<S: AsRef<str>>(&mut self, fields: &[S]) {
+        for (index, field) in fields.iter().enumerate() {
+            if index > 0 {
+                self.content.push(self.delimiter);
+            }
+            self.field(field.as_ref());
+        }
+        self.content.push_str("\r\n");
+    }
+
+    fn field(&mut self, value: &str) {
+        let needs_quotes = value.contains([self.delimiter, '"', '\r', '\n']);
+        if needs_quotes {
+            self.content.push('"');
+            self.content.push_str(&value.replace('"', "\"\""));
+            self.content.push('"');
+        } else {
+            self.content.push_str(value);
+        }
+    }
+
+    fn decimal(&self, value: Decimal) -> String {
+        value.normalize().to_string().replace('.', &self.decimal_separator.to_string())
+    }
+
+    fn calculation_fields(&self, calculation: &CalculationResult) -> Vec<String> {
+        vec![
+            calculation.film_name.clone(),
+            calculation.film_type.label().to_string(),
+            calculation.developer_name.clone(),
+            calculation.dilution.clone(),
+            self.decimal(calculation.temperature),
+            calculation.push_pull.to_string(),
+            calculation.time_formatted.clone(),
+            self.decimal(calculation.time_minutes),
+            calculation.developer_amount.to_string(),
+            calculation.water_amount.to_string(),
+            (calculation.developer_amount + calculation.water_amount).to_string(),
+            calculation.notes.join("; "),
+        ]
+    }
+
+    fn finish(self) -> String {
+        self.content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::engine;
+
+    #[test]
+    fn films_without_developments_fill_every_column() {
+        let mut summary = engine().database_summary(&FilmQuery::default()).unwrap();
+        let film = summary.films.iter_mut().find(|entry| entry.film.key == "ilford_hp5_plus").unwrap();
+        film.developments.clear();
+        let options = CsvOptions { delimiter: Some(';'), ..Default::default() };
+
+        let csv = database_summary_csv(&summary, &options).unwrap();
+        let columns = csv.lines().next().unwrap().split(';').count();
+        let row = csv.lines().find(|line| line.starts_with("ilford_hp5_plus;")).unwrap();
+        assert_eq!(row.split(';').count(), columns);
+        assert!(row.ends_with(&";".repeat(columns - 6)));
+    }
+}
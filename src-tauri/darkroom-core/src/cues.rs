@@ -0,0 +1,163 @@
+#[cfg(feature = "export")]
+use crate::export::{ExportError, ExportManager};
+use crate::models::*;
+
+// How long the closing cue stays on screen after the last step
+const COMPLETE_CUE_SECONDS: u32 = 5;
+
+#[cfg(feature = "export")]
+impl ExportManager {
+    pub fn export_cues(
+        &self,
+        plan: &ProcessPlan,
+        format: CueFormat,
+        file_path: Option<String>,
+    ) -> Result<String, ExportError> {
+        let cue_list = build_cue_list(plan);
+
+        let (content, label, extension) = match format {
+            CueFormat::WebVtt => (to_webvtt(&cue_list), "WebVTT", "vtt"),
+            CueFormat::Srt => (to_srt(&cue_list), "SRT", "srt"),
+            CueFormat::Json => (serde_json::to_string_pretty(&cue_list)?, "JSON", "json"),
+        };
+
+        let path = self.write_file(file_path.as_deref(), &[&plan.name], extension, content.as_bytes())?;
+        Ok(format!("Exported cues to {} file: {}", label, path))
+    }
+}
+
+/// Splits the plan into back-to-back cues, one per hands-on action plus the
+/// standing time between them, so players that cannot overlap cues show them all
+pub fn build_cue_list(plan: &ProcessPlan) -> CueList {
+    let actions = plan.actions();
+    let step_count = plan.steps.len();
+    let mut cues: Vec<Cue> = Vec::new();
+    let mut push = |kind, step_index, start_seconds, end_seconds, title: &str, text: String| {
+        if end_seconds > start_seconds {
+            cues.push(Cue {
+                index: cues.len() + 1,
+                kind,
+                step_index,
+                start_seconds,
+                end_seconds,
+                title: title.to_string(),
+                text,
+            });
+        }
+    };
+
+    for (step_index, (step, start)) in plan.steps.iter().zip(plan.step_starts()).enumerate() {
+        let end = start + step.duration_seconds;
+        let title = match step.temperature {
+            Some(temperature) => format!("{}/{} {} ({}°C)", step_index + 1, step_count, step.name, temperature.normalize()),
+            None => format!("{}/{} {}", step_index + 1, step_count, step.name),
+        };
+
+        let mut time = start;
+        for (position, action) in actions.iter().enumerate().filter(|(_, action)| action.step_index == step_index) {
+            push(CueKind::Wait, step_index, time, action.start_seconds, &title, wait_text(action, &plan.steps));
+
+            let action_end = action.start_seconds + action.duration_seconds;
+            let text = match action.kind {
+                ProcessActionKind::Pour => format!("POUR {}", step.name.to_uppercase()),
+                ProcessActionKind::Agitate => format!("AGITATE for {}s", action.duration_seconds),
+                ProcessActionKind::Drain => match actions.get(position + 1) {
+                    Some(next) if next.step_index != step_index => format!(
+                        "DRAIN {}, {} next",
+                        step.name.to_uppercase(),
+                        plan.steps[next.step_index].name.to_lowercase()
+                    ),
+                    _ => format!("DRAIN {}", step.name.to_uppercase()),
+                },
+            };
+            push(cue_kind(action.kind), step_index, action.start_seconds, action_end, &title, text);
+            time = time.max(action_end);
+        }
+
+        let text = match actions.iter().find(|action| action.start_seconds >= end) {
+            Some(next) => wait_text(next, &plan.steps),
+            None => format!("Stand until {}", clock(end)),
+        };
+        push(CueKind::Wait, step_index, time, end, &title, text);
+    }
+
+    let total_seconds = plan.total_seconds();
+    push(
+        CueKind::Complete,
+        step_count.saturating_sub(1),
+        total_seconds,
+        total_seconds + COMPLETE_CUE_SECONDS,
+        &plan.name,
+        "Process complete".to_string(),
+    );
+
+    CueList {
+        name: plan.name.clone(),
+        total_seconds,
+        cues,
+    }
+}
+
+#[cfg(feature = "export")]
+fn to_webvtt(cue_list: &CueList) -> String {
+    let mut content = format!("WEBVTT - {}\n\n", cue_list.name.replace("-->", "->"));
+    for cue in &cue_list.cues {
+        content.push_str(&format!(
+            "{}\n{} --> {}\n{}\n{}\n\n",
+            cue.index,
+            timestamp(cue.start_seconds, '.'),
+            timestamp(cue.end_seconds, '.'),
+            cue.title,
+            cue.text
+        ));
+    }
+    content
+}
+
+#[cfg(feature = "export")]
+fn to_srt(cue_list: &CueList) -> String {
+    let mut content = String::new();
+    for cue in &cue_list.cues {
+        content.push_str(&format!(
+            "{}\n{} --> {}\n{}\n{}\n\n",
+            cue.index,
+            timestamp(cue.start_seconds, ','),
+            timestamp(cue.end_seconds, ','),
+            cue.title,
+            cue.text
+        ));
+    }
+    content
+}
+
+fn cue_kind(kind: ProcessActionKind) -> CueKind {
+    match kind {
+        ProcessActionKind::Pour => CueKind::Pour,
+        ProcessActionKind::Agitate => CueKind::Agitate,
+        ProcessActionKind::Drain => CueKind::Drain,
+    }
+}
+
+fn wait_text(next: &ProcessAction, steps: &[ProcessStep]) -> String {
+    let action = match next.kind {
+        ProcessActionKind::Pour => format!("pour {}", steps[next.step_index].name.to_lowercase()),
+        ProcessActionKind::Agitate => "agitate".to_string(),
+        ProcessActionKind::Drain => "drain".to_string(),
+    };
+    format!("Stand, {} at {}", action, clock(next.start_seconds))
+}
+
+/// Process time as the player shows it, e.g. 7:30 or 1:02:00
+pub fn clock(seconds: u32) -> String {
+    if seconds >= 3600 {
+        format!("{}:{:02}:{:02}", seconds / 3600, seconds % 3600 / 60, seconds % 60)
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+// WebVTT separates milliseconds with a dot, SRT with a comma
+#[cfg(feature = "export")]
+fn timestamp(seconds: u32, separator: char) -> String {
+    format!("{:02}:{:02}:{:02}{}000", seconds / 3600, seconds % 3600 / 60, seconds % 60, separator)
+}
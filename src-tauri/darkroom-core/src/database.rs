@@ -4,6 +4,7 @@ use std::fs;
 use std::path::Path;
 use thiserror::Error;
 
+/// Why a database file could not be loaded.
 #[derive(Error, Debug)]
 pub enum DatabaseError {
     #[error("Failed to read database file: {0}")]
@@ -16,15 +17,19 @@ pub enum DatabaseError {
     InvalidStructure(String),
 }
 
+/// Loads and validates a database in the `complete_database.json` format.
+#[derive(Default)]
 pub struct DatabaseManager {
     database: Option<Database>,
 }
 
 impl DatabaseManager {
+    /// A manager with no database loaded.
     pub fn new() -> Self {
         Self { database: None }
     }
 
+    /// Reads, parses and validates a database file.
     pub fn load_from_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), DatabaseError> {
         let path_str = path.as_ref().to_string_lossy().to_string();
         
@@ -42,6 +47,7 @@ impl DatabaseManager {
         Ok(())
     }
 
+    /// Parses and validates a database from JSON text.
     pub fn load_from_json(&mut self, json_content: &str) -> Result<(), DatabaseError> {
         let database: Database = serde_json::from_str(json_content)?;
         self.validate_database(&database)?;
@@ -49,10 +55,12 @@ impl DatabaseManager {
         Ok(())
     }
 
+    /// The loaded database, if any.
     pub fn get_database(&self) -> Option<&Database> {
         self.database.as_ref()
     }
 
+    /// The loaded database, to hand to [`crate::CalculationEngine::load_database`].
     pub fn take_database(self) -> Option<Database> {
         self.database
     }
@@ -87,6 +95,7 @@ impl DatabaseManager {
         Ok(())
     }
 
+    /// Film, developer and combination counts of the loaded database.
     pub fn get_stats(&self) -> Option<DatabaseStats> {
         self.database.as_ref().map(|db| {
             let total_combinations = db.films.values()
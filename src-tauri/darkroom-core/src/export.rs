@@ -0,0 +1,231 @@
+use crate::csv::{calculations_csv, database_summary_csv};
+use crate::export_files::ExportFiles;
+use crate::markup::{database_summary_markdown, recipe_html, recipe_markdown};
+use crate::models::*;
+use crate::pdf::{render_database_summary, render_recipe_card};
+use crate::template::{TemplateError, TemplateLibrary};
+use chrono::Utc;
+use serde_json;
+use thiserror::Error;
+
+/// Written into every ExportData, increase it when the layout changes.
+/// Version 2 added the version itself and the calculation request.
+pub const EXPORT_FORMAT_VERSION: u32 = 2;
+const SUMMARY_FILE_NAME: &str = "DarkroomPro catalogue";
+
+/// Why an export failed.
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error("Failed to write file: {0}")]
+    FileWriteError(#[from] std::io::Error),
+    #[error("Failed to serialize data: {0}")]
+    SerializationError(#[from] serde_json::Error),
+    #[error("Unsupported export format: {0:?}")]
+    UnsupportedFormat(ExportFormat),
+    #[error("Invalid file path: {0}")]
+    InvalidPath(String),
+    #[error("Failed to generate PDF: {0}")]
+    PdfError(#[from] printpdf::Error),
+    #[error("Invalid CSV options: {0}")]
+    InvalidCsvOptions(String),
+    #[error(transparent)]
+    TemplateError(#[from] TemplateError),
+    #[error("Nothing to put on the calendar, give a process plan, a session or chemistry batches")]
+    EmptyCalendar,
+    #[error("File already exists: {0}")]
+    FileExists(String),
+    #[error("Failed to create QR code: {0}")]
+    QrError(#[from] qrcode::types::QrError),
+    #[error("Failed to encode PNG: {0}")]
+    PngError(#[from] png::EncodingError),
+}
+
+/// Writes calculations, recipes, charts and catalogues in the supported
+/// formats. Every export is a file in a folder approved in its [`ExportFiles`],
+/// and the returned message names it. Without a path the file goes to the
+/// default folder, named after the film, developer or plan and today's date.
+/// For the content as text, call the function that builds it instead, e.g.
+/// `markup::recipe_markdown` or `csv::calculations_csv`.
+#[derive(Default)]
+pub struct ExportManager {
+    templates: Option<TemplateLibrary>,
+    files: ExportFiles,
+}
+
+impl ExportManager {
+    /// A manager without user templates that writes no files.
+    pub fn new() -> Self {
+        Self {
+            templates: None,
+            files: ExportFiles::default(),
+        }
+    }
+
+    /// Needed before exporting with ExportFormat::Template
+    pub fn with_templates(mut self, templates: TemplateLibrary) -> Self {
+        self.templates = Some(templates);
+        self
+    }
+
+    /// Needed before writing any file, see ExportFiles
+    pub fn with_files(mut self, files: ExportFiles) -> Self {
+        self.files = files;
+        self
+    }
+
+    // Every export file goes through here, returns where it was written
+    pub(crate) fn write_file(
+        &self,
+        file_path: Option<&str>,
+        name: &[&str],
+        extension: &str,
+        content: &[u8],
+    ) -> Result<String, ExportError> {
+        Ok(self.files.write(file_path, name, extension, content)?.display().to_string())
+    }
+
+    pub fn export_calculation(
+        &self,
+        calculation: &CalculationResult,
+        request: Option<&CalculationRequest>,
+        format: ExportFormat,
+        file_path: Option<String>,
+    ) -> Result<String, ExportError> {
+        let timestamp = Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string();
+        
+        let export_data = ExportData {
+            version: EXPORT_FORMAT_VERSION,
+            request: request.cloned(),
+            calculation: calculation.clone(),
+            plan: None,
+            safety_warnings: Vec::new(),
+            timestamp: timestamp.clone(),
+            format: format.clone(),
+        };
+
+        match &format {
+            ExportFormat::Json => self.export_json(&export_data, file_path),
+            ExportFormat::Csv => self.export_csv(&export_data, file_path),
+            ExportFormat::Pdf => self.export_pdf(&export_data, file_path),
+            ExportFormat::Markdown => self.export_markdown(&export_data, file_path),
+            ExportFormat::Html => self.export_html(&export_data, file_path),
+            ExportFormat::Template(name) => self.export_template(&export_data, name, file_path),
+        }
+    }
+
+    /// The full recipe with every process step, in any format
+    pub fn export_recipe(
+        &self,
+        card: &RecipeCard,
+        request: &CalculationRequest,
+        format: ExportFormat,
+        page_size: PageSize,
+        file_path: Option<String>,
+    ) -> Result<String, ExportError> {
+        let timestamp = Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string();
+
+        let export_data = ExportData {
+            version: EXPORT_FORMAT_VERSION,
+            request: Some(request.clone()),
+            calculation: card.calculation.clone(),
+            plan: card.plan.clone(),
+            safety_warnings: card.safety_warnings.clone(),
+            timestamp: timestamp.clone(),
+            format: format.clone(),
+        };
+
+        let (content, label) = match &format {
+            ExportFormat::Template(name) => return self.export_template(&export_data, name, file_path),
+            ExportFormat::Pdf => return self.export_recipe_card(card, page_size, file_path),
+            ExportFormat::Csv => (calculations_csv(std::slice::from_ref(&card.calculation), &CsvOptions::default())?, "CSV"),
+            ExportFormat::Json => return self.export_json(&export_data, file_path),
+            ExportFormat::Markdown => (recipe_markdown(card, &timestamp), "Markdown"),
+            ExportFormat::Html => (recipe_html(card, &timestamp), "HTML"),
+        };
+
+        let name = [card.calculation.film_name.as_str(), &card.calculation.developer_name];
+        let path = self.write_file(file_path.as_deref(), &name, format.extension(), content.as_bytes())?;
+        Ok(format!("Exported recipe to {} file: {}", label, path))
+    }
+
+    fn export_template(&self, data: &ExportData, name: &str, file_path: Option<String>) -> Result<String, ExportError> {
+        let templates = self
+            .templates
+            .as_ref()
+            .ok_or_else(|| TemplateError::NotFound(name.to_string()))?;
+        let content = templates.render(name, data)?;
+        let extension = templates.find(name)?.extension.unwrap_or_else(|| "txt".to_string());
+
+        let path = self.write_file(file_path.as_deref(), &[&data.calculation.film_name, &data.calculation.developer_name], &extension, content.as_bytes())?;
+        Ok(format!("Exported with template {} to file: {}", name, path))
+    }
+
+    fn export_json(&self, data: &ExportData, file_path: Option<String>) -> Result<String, ExportError> {
+        let json_content = serde_json::to_string_pretty(data)?;
+        
+        let path = self.write_file(file_path.as_deref(), &[&data.calculation.film_name, &data.calculation.developer_name], "json", json_content.as_bytes())?;
+        Ok(format!("Exported to JSON file: {}", path))
+    }
+
+    fn export_csv(&self, data: &ExportData, file_path: Option<String>) -> Result<String, ExportError> {
+        let csv_content = calculations_csv(std::slice::from_ref(&data.calculation), &CsvOptions::default())?;
+
+        let path = self.write_file(file_path.as_deref(), &[&data.calculation.film_name, &data.calculation.developer_name], "csv", csv_content.as_bytes())?;
+        Ok(format!("Exported to CSV file: {}", path))
+    }
+
+    fn export_pdf(&self, data: &ExportData, file_path: Option<String>) -> Result<String, ExportError> {
+        let content = render_recipe_card(&calculation_card(data), PageSize::A4, &data.timestamp)?;
+        let path = self.write_file(file_path.as_deref(), &[&data.calculation.film_name, &data.calculation.developer_name], "pdf", &content)?;
+        Ok(format!("Exported to PDF file: {}", path))
+    }
+
+    fn export_markdown(&self, data: &ExportData, file_path: Option<String>) -> Result<String, ExportError> {
+        let markdown_content = recipe_markdown(&calculation_card(data), &data.timestamp);
+
+        let path = self.write_file(file_path.as_deref(), &[&data.calculation.film_name, &data.calculation.developer_name], "md", markdown_content.as_bytes())?;
+        Ok(format!("Exported to Markdown file: {}", path))
+    }
+
+    fn export_html(&self, data: &ExportData, file_path: Option<String>) -> Result<String, ExportError> {
+        let html_content = recipe_html(&calculation_card(data), &data.timestamp);
+
+        let path = self.write_file(file_path.as_deref(), &[&data.calculation.film_name, &data.calculation.developer_name], "html", html_content.as_bytes())?;
+        Ok(format!("Exported to HTML file: {}", path))
+    }
+
+    /// The catalogue from CalculationEngine::database_summary, with every development time
+    pub fn export_database_summary(
+        &self,
+        summary: &DatabaseSummary,
+        format: ExportFormat,
+        csv_options: &CsvOptions,
+        file_path: Option<String>,
+    ) -> Result<String, ExportError> {
+        let timestamp = Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string();
+
+        let (content, label) = match &format {
+            ExportFormat::Csv => (database_summary_csv(summary, csv_options)?, "CSV"),
+            ExportFormat::Json => (serde_json::to_string_pretty(summary)?, "JSON"),
+            ExportFormat::Markdown => (database_summary_markdown(summary, &timestamp), "Markdown"),
+            ExportFormat::Pdf => {
+                let content = render_database_summary(summary, &timestamp)?;
+                let path = self.write_file(file_path.as_deref(), &[SUMMARY_FILE_NAME], "pdf", &content)?;
+                return Ok(format!("Database summary exported to PDF file: {}", path));
+            },
+            ExportFormat::Html | ExportFormat::Template(_) => return Err(ExportError::UnsupportedFormat(format)),
+        };
+
+        let path = self.write_file(file_path.as_deref(), &[SUMMARY_FILE_NAME], format.extension(), content.as_bytes())?;
+        Ok(format!("Database summary exported to {} file: {}", label, path))
+    }
+}
+
+// A card for a single calculation, which has no process plan to go with it
+fn calculation_card(data: &ExportData) -> RecipeCard {
+    RecipeCard {
+        calculation: data.calculation.clone(),
+        plan: None,
+        safety_warnings: Vec::new(),
+    }
+}
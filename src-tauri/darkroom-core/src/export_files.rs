@@ -0,0 +1,447 @@
+use crate::export::ExportError;
+use chrono::Local;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+// Generated names get a counter rather than replacing an earlier export, up to this many
+const MAX_NAME_ATTEMPTS: u32 = 999;
+// Well under the 255 byte file name limit of common file systems, leaving room for the date
+const MAX_STEM_CHARS: usize = 100;
+// Temporary files are numbered so concurrent writes in one process never share one
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Where exports may be written and whether an existing file may be replaced.
+/// The default approves no folder, so nothing is written until it is configured.
+#[derive(Debug, Clone, Default)]
+pub struct ExportFiles {
+    default_directory: Option<PathBuf>,
+    directories: Vec<PathBuf>,
+    overwrite: bool,
+}
+
+impl ExportFiles {
+    /// The default directory is approved too, and created on first use
+    pub fn new(default_directory: PathBuf, directories: Vec<PathBuf>) -> Self {
+        Self {
+            default_directory: Some(default_directory),
+            directories,
+            overwrite: false,
+        }
+    }
+
+    pub fn allow_overwrite(mut self, overwrite: bool) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// Writes to the given file, or to a file named after the name parts and
+    /// today's date when given an approved folder or no path at all
+    pub fn write(
+        &self,
+        file_path: Option<&str>,
+        name: &[&str],
+        extension: &str,
+        content: &[u8],
+    ) -> Result<PathBuf, ExportError> {
+        let (directory, file_name) = match file_path {
+            None => {
+                let directory = self
+                    .default_directory
+                    .clone()
+                    .ok_or_else(|| ExportError::InvalidPath("No export folder is set up".to_string()))?;
+                fs::create_dir_all(&directory)?;
+                (directory, None)
+            },
+            Some(file_path) => {
+                let path = PathBuf::from(file_path);
+                if !path.is_absolute() {
+                    return Err(ExportError::InvalidPath(format!("{} is not an absolute path", file_path)));
+                }
+                if path.is_dir() {
+                    (path, None)
+                } else {
+                    match (path.parent(), path.file_name()) {
+                        (Some(parent), Some(file_name)) => (parent.to_path_buf(), Some(file_name.to_os_string())),
+                        _ => return Err(ExportError::InvalidPath(format!("{} is not a file name", file_path))),
+                    }
+                }
+            },
+        };
+
+        let directory = self.approved_directory(&directory)?;
+        let target = match file_name {
+            Some(file_name) => {
+                let target = directory.join(file_name);
+                check_target(&target, self.overwrite)?;
+                target
+            },
+            None => unused_name(&directory, &generated_stem(name), extension)?,
+        };
+
+        write_atomically(&target, content, self.overwrite)?;
+        Ok(target)
+    }
+
+    /// Reads a file back, e.g. for an import, as long as it is inside an approved folder
+    pub fn read(&self, file_path: &str) -> Result<String, ExportError> {
+        let path = PathBuf::from(file_path);
+        if !path.is_absolute() {
+            return Err(ExportError::InvalidPath(format!("{} is not an absolute path", file_path)));
+        }
+        let canonical = fs::canonicalize(&path)
+            .map_err(|_| ExportError::InvalidPath(format!("File {} does not exist", file_path)))?;
+        if !canonical.is_file() {
+            return Err(ExportError::InvalidPath(format!("{} is not a regular file", file_path)));
+        }
+        if !self.is_approved(&canonical) {
+            return Err(ExportError::InvalidPath(format!("{} is not in an approved export folder", file_path)));
+        }
+
+        Ok(fs::read_to_string(canonical)?)
+    }
+
+    // The folder with links resolved, if it is inside an approved one
+    fn approved_directory(&self, directory: &Path) -> Result<PathBuf, ExportError> {
+        let canonical = fs::canonicalize(directory)
+            .map_err(|_| ExportError::InvalidPath(format!("Folder {} does not exist", directory.display())))?;
+
+        if self.is_approved(&canonical) {
+            Ok(canonical)
+        } else {
+            Err(ExportError::InvalidPath(format!(
+                "{} is not an approved export folder",
+                directory.display()
+            )))
+        }
+    }
+
+    // Expects a path with links already resolved
+    fn is_approved(&self, canonical: &Path) -> bool {
+        self.default_directory
+            .iter()
+            .chain(&self.directories)
+            .filter_map(|approved| fs::canonicalize(approved).ok())
+            .any(|approved| canonical.starts_with(approved))
+    }
+}
+
+/// Folders the user picked for exports, kept in a JSON file in the app data directory
+pub struct ExportDirectories {
+    path: PathBuf,
+    default_directory: PathBuf,
+    approved: Mutex<Vec<PathBuf>>,
+}
+
+impl ExportDirectories {
+    /// A missing or unreadable file starts with only the default folder
+    pub fn load(path: PathBuf, default_directory: PathBuf) -> Self {
+        let approved = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            default_directory,
+            approved: Mutex::new(approved),
+        }
+    }
+
+    /// The default folder first, then the approved ones in the order they were added
+    pub fn list(&self) -> Vec<PathBuf> {
+        let mut directories = vec![self.default_directory.clone()];
+        directories.extend(self.approved.lock().unwrap().iter().cloned());
+        directories
+    }
+
+    /// Refuses filesystem roots and the home folder, which would open every file in them to exports
+    pub fn approve(&self, directory: &Path) -> Result<Vec<PathBuf>, ExportError> {
+        if !directory.is_absolute() || !directory.is_dir() {
+            return Err(ExportError::InvalidPath(format!("{} is not a folder", directory.display())));
+        }
+        let canonical = fs::canonicalize(directory)?;
+        let contains_home = home_directory().is_some_and(|home| home.starts_with(&canonical));
+        if canonical.parent().is_none() || contains_home {
+            return Err(ExportError::InvalidPath(format!(
+                "{} is too broad for exports, pick a folder inside it",
+                directory.display()
+            )));
+        }
+
+        {
+            let mut approved = self.approved.lock().unwrap();
+            if !approved.contains(&canonical) {
+                approved.push(canonical);
+                self.save(&approved)?;
+            }
+        }
+        Ok(self.list())
+    }
+
+    pub fn revoke(&self, directory: &str) -> Result<Vec<PathBuf>, ExportError> {
+        let path = PathBuf::from(directory);
+        let canonical = fs::canonicalize(&path).unwrap_or(path);
+
+        {
+            let mut approved = self.approved.lock().unwrap();
+            let count = approved.len();
+            approved.retain(|approved| *approved != canonical);
+            if approved.len() != count {
+                self.save(&approved)?;
+            }
+        }
+        Ok(self.list())
+    }
+
+    pub fn files(&self, overwrite: bool) -> ExportFiles {
+        ExportFiles::new(self.default_directory.clone(), self.approved.lock().unwrap().clone()).allow_overwrite(overwrite)
+    }
+
+    fn save(&self, approved: &[PathBuf]) -> Result<(), ExportError> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        write_atomically(&self.path, serde_json::to_string_pretty(approved)?.as_bytes(), true)
+    }
+}
+
+fn home_directory() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .and_then(|home| fs::canonicalize(home).ok())
+}
+
+fn check_target(target: &Path, overwrite: bool) -> Result<(), ExportError> {
+    let Ok(metadata) = fs::symlink_metadata(target) else {
+        return Ok(());
+    };
+    // A link could point anywhere, outside the approved folders included
+    if metadata.file_type().is_symlink() || metadata.is_dir() {
+        return Err(ExportError::InvalidPath(format!("{} is not a regular file", target.display())));
+    }
+    if !overwrite {
+        return Err(ExportError::FileExists(target.display().to_string()));
+    }
+    Ok(())
+}
+
+// e.g. "ilford-hp5-plus_kodak-d-76_2026-10-18"
+fn generated_stem(name: &[&str]) -> String {
+    let mut parts: Vec<String> = name.iter().map(|part| slug(part)).filter(|part| !part.is_empty()).collect();
+    if parts.is_empty() {
+        parts.push("darkroompro".to_string());
+    }
+
+    let mut stem: String = parts.join("_").chars().take(MAX_STEM_CHARS).collect();
+    stem.push('_');
+    stem.push_str(&Local::now().format("%Y-%m-%d").to_string());
+    stem
+}
+
+// Lowercase letters and digits with single dashes between words
+fn slug(text: &str) -> String {
+    let mut slug = String::new();
+    for character in text.chars() {
+        if character.is_alphanumeric() {
+            slug.extend(character.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+fn unused_name(directory: &Path, stem: &str, extension: &str) -> Result<PathBuf, ExportError> {
+    for attempt in 1..=MAX_NAME_ATTEMPTS {
+        let file_name = if attempt == 1 {
+            format!("{}.{}", stem, extension)
+        } else {
+            format!("{}-{}.{}", stem, attempt, extension)
+        };
+        let target = directory.join(file_name);
+        if fs::symlink_metadata(&target).is_err() {
+            return Ok(target);
+        }
+    }
+    Err(ExportError::FileExists(directory.join(format!("{}.{}", stem, extension)).display().to_string()))
+}
+
+// Writes next to the target and moves it into place, so a crash or a full
+// disk never leaves half a file where the export should be. Without overwrite
+// the move fails rather than replace a file that appeared in the meantime.
+fn write_atomically(target: &Path, content: &[u8], overwrite: bool) -> Result<(), ExportError> {
+    let (temp_path, mut file) = create_temp_file(target)?;
+    let written = file.write_all(content).and_then(|_| file.sync_all());
+    drop(file);
+
+    let result = written
+        .map_err(ExportError::from)
+        .and_then(|_| publish(&temp_path, target, overwrite));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+// A new file next to the target; create_new also refuses a link planted at the temporary name
+fn create_temp_file(target: &Path) -> Result<(PathBuf, fs::File), ExportError> {
+    let file_name = target
+        .file_name()
+        .ok_or_else(|| ExportError::InvalidPath(format!("{} is not a file name", target.display())))?
+        .to_string_lossy()
+        .to_string();
+
+    loop {
+        let temp_path = target.with_file_name(format!(
+            ".{}.{}.{}.tmp",
+            file_name,
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        match fs::OpenOptions::new().write(true).create_new(true).open(&temp_path) {
+            Ok(file) => return Ok((temp_path, file)),
+            // Left behind by an earlier run with the same process id
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+fn publish(temp_path: &Path, target: &Path, overwrite: bool) -> Result<(), ExportError> {
+    if overwrite {
+        return Ok(fs::rename(temp_path, target)?);
+    }
+
+    // A hard link, unlike a rename, fails when the target exists
+    match fs::hard_link(temp_path, target) {
+        Ok(()) => {
+            let _ = fs::remove_file(temp_path);
+            Ok(())
+        },
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => Err(ExportError::FileExists(target.display().to_string())),
+        // File systems without hard links, e.g. FAT on memory cards: claim the name, then copy
+        Err(_) => {
+            let mut file = fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(target)
+                .map_err(|e| match e.kind() {
+                    ErrorKind::AlreadyExists => ExportError::FileExists(target.display().to_string()),
+                    _ => ExportError::from(e),
+                })?;
+            let result = fs::read(temp_path).and_then(|content| {
+                file.write_all(&content)?;
+                file.sync_all()
+            });
+            if result.is_err() {
+                let _ = fs::remove_file(target);
+            }
+            let _ = fs::remove_file(temp_path);
+            Ok(result?)
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A fresh folder per test, so tests running in parallel never share files
+    fn test_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("darkroom-export-files-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    fn file_names(directory: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn a_file_that_appears_before_publishing_is_not_replaced() {
+        let directory = test_directory("no-clobber");
+        let target = directory.join("recipe.md");
+        fs::write(&target, "someone else's").unwrap();
+
+        let result = write_atomically(&target, b"export", false);
+        assert!(matches!(result, Err(ExportError::FileExists(_))));
+        assert_eq!(fs::read_to_string(&target).unwrap(), "someone else's");
+        assert_eq!(file_names(&directory), vec!["recipe.md"]);
+
+        write_atomically(&target, b"export", true).unwrap();
+        assert_eq!(fs::read_to_string(&target).unwrap(), "export");
+        assert_eq!(file_names(&directory), vec!["recipe.md"]);
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn temporary_files_are_unique_and_never_follow_links() {
+        let directory = test_directory("temp-names");
+        let target = directory.join("recipe.md");
+
+        let (first, _first_file) = create_temp_file(&target).unwrap();
+        let (second, _second_file) = create_temp_file(&target).unwrap();
+        assert_ne!(first, second);
+        assert!(first.file_name().unwrap().to_string_lossy().starts_with(".recipe.md."));
+
+        #[cfg(unix)]
+        {
+            let outside = directory.join("outside.txt");
+            fs::write(&outside, "untouched").unwrap();
+            let planted = target.with_file_name(format!(
+                ".recipe.md.{}.{}.tmp",
+                std::process::id(),
+                TEMP_COUNTER.load(Ordering::Relaxed)
+            ));
+            std::os::unix::fs::symlink(&outside, &planted).unwrap();
+
+            let (temp_path, _file) = create_temp_file(&target).unwrap();
+            assert_ne!(temp_path, planted);
+            assert_eq!(fs::read_to_string(&outside).unwrap(), "untouched");
+        }
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn generated_names_count_up_instead_of_replacing() {
+        let directory = test_directory("generated");
+        let files = ExportFiles::new(directory.clone(), Vec::new());
+
+        let first = files.write(None, &["Ilford HP5 Plus", "Kodak D-76"], "md", b"one").unwrap();
+        let second = files.write(None, &["Ilford HP5 Plus", "Kodak D-76"], "md", b"two").unwrap();
+        let stem = generated_stem(&["Ilford HP5 Plus", "Kodak D-76"]);
+        assert_eq!(first, directory.join(format!("{}.md", stem)));
+        assert_eq!(second, directory.join(format!("{}-2.md", stem)));
+        assert_eq!(fs::read_to_string(first).unwrap(), "one");
+        assert_eq!(file_names(&directory).len(), 2);
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn files_outside_approved_folders_are_refused() {
+        let directory = test_directory("confined");
+        let approved = directory.join("approved");
+        let outside = directory.join("outside");
+        fs::create_dir_all(&approved).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        let files = ExportFiles::new(approved.clone(), Vec::new());
+
+        let escaping = approved.join("..").join("outside").join("recipe.md");
+        assert!(matches!(
+            files.write(Some(&escaping.display().to_string()), &[], "md", b"x"),
+            Err(ExportError::InvalidPath(_))
+        ));
+        fs::write(outside.join("recipe.json"), "{}").unwrap();
+        assert!(files.read(&outside.join("recipe.json").display().to_string()).is_err());
+        assert_eq!(file_names(&outside), vec!["recipe.json"]);
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
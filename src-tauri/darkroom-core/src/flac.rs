@@ -0,0 +1,223 @@
+// Minimal FLAC encoder for mono 16-bit audio. Silent blocks are stored as
+// constant subframes and everything else with a fixed second-order predictor
+// and Rice-coded residuals, falling back to verbatim samples when smaller.
+
+const BLOCK_SIZE: usize = 4096;
+const PREDICTOR_ORDER: usize = 2;
+// Rice parameters above this would need the escape code
+const MAX_RICE_PARAMETER: u32 = 14;
+
+pub fn encode_flac(samples: &[i16], sample_rate: u32) -> Vec<u8> {
+    let mut output = Vec::with_capacity(samples.len() / 2);
+    output.extend_from_slice(b"fLaC");
+    write_stream_info(&mut output, samples.len() as u64, sample_rate);
+
+    for (frame_number, block) in samples.chunks(BLOCK_SIZE).enumerate() {
+        write_frame(&mut output, frame_number as u32, block);
+    }
+
+    output
+}
+
+fn write_stream_info(output: &mut Vec<u8>, total_samples: u64, sample_rate: u32) {
+    let mut writer = BitWriter::new();
+    // Last metadata block, type 0 (STREAMINFO), 34 bytes long
+    writer.write(1, 1);
+    writer.write(0, 7);
+    writer.write(34, 24);
+
+    writer.write(BLOCK_SIZE as u64, 16);
+    writer.write(BLOCK_SIZE as u64, 16);
+    // Minimum and maximum frame sizes are left as unknown
+    writer.write(0, 24);
+    writer.write(0, 24);
+    writer.write(u64::from(sample_rate), 20);
+    writer.write(0, 3); // one channel
+    writer.write(15, 5); // 16 bits per sample
+    writer.write(total_samples, 36);
+    // An all-zero MD5 signature means it was not computed
+    writer.write(0, 64);
+    writer.write(0, 64);
+
+    output.extend_from_slice(&writer.finish());
+}
+
+fn write_frame(output: &mut Vec<u8>, frame_number: u32, block: &[i16]) {
+    let mut writer = BitWriter::new();
+
+    writer.write(0b11_1111_1111_1110, 14); // sync code
+    writer.write(0, 1);
+    writer.write(0, 1); // fixed block size
+    writer.write(0b0111, 4); // block size as 16 bits at the end of the header
+    writer.write(0b0000, 4); // sample rate from STREAMINFO
+    writer.write(0b0000, 4); // mono
+    writer.write(0b100, 3); // 16 bits per sample
+    writer.write(0, 1);
+    for byte in utf8_number(frame_number) {
+        writer.write(u64::from(byte), 8);
+    }
+    writer.write(block.len() as u64 - 1, 16);
+    let header_crc = crc8(writer.bytes());
+    writer.write(u64::from(header_crc), 8);
+
+    write_subframe(&mut writer, block);
+
+    let mut frame = writer.finish();
+    let frame_crc = crc16(&frame);
+    frame.extend_from_slice(&frame_crc.to_be_bytes());
+    output.extend_from_slice(&frame);
+}
+
+fn write_subframe(writer: &mut BitWriter, block: &[i16]) {
+    if block.iter().all(|&sample| sample == block[0]) {
+        writer.write(0, 1);
+        writer.write(0b000000, 6); // constant
+        writer.write(0, 1);
+        writer.write_signed(i64::from(block[0]), 16);
+        return;
+    }
+
+    let residuals: Vec<i64> = if block.len() > PREDICTOR_ORDER {
+        block
+            .windows(3)
+            .map(|window| i64::from(window[2]) - 2 * i64::from(window[1]) + i64::from(window[0]))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let encoded: Vec<u64> = residuals.iter().map(|&residual| zigzag(residual)).collect();
+    let best = (0..=MAX_RICE_PARAMETER)
+        .map(|parameter| (parameter, rice_bits(&encoded, parameter)))
+        .min_by_key(|&(_, bits)| bits);
+
+    // Header, warm-up samples, coding method, partition order and parameter
+    let fixed_bits = best.map(|(_, bits)| 8 + 16 * PREDICTOR_ORDER as u64 + 2 + 4 + 4 + bits);
+    let verbatim_bits = 8 + 16 * block.len() as u64;
+
+    match (fixed_bits, best) {
+        (Some(fixed_bits), Some((parameter, _))) if !residuals.is_empty() && fixed_bits < verbatim_bits => {
+            writer.write(0, 1);
+            writer.write(0b001000 | PREDICTOR_ORDER as u64, 6); // fixed predictor
+            writer.write(0, 1);
+            for &sample in &block[..PREDICTOR_ORDER] {
+                writer.write_signed(i64::from(sample), 16);
+            }
+            writer.write(0b00, 2); // 4-bit Rice parameters
+            writer.write(0, 4); // a single partition
+            writer.write(u64::from(parameter), 4);
+            for &value in &encoded {
+                let quotient = value >> parameter;
+                writer.write_zeros(quotient);
+                writer.write(1, 1);
+                writer.write(value & ((1 << parameter) - 1), parameter);
+            }
+        },
+        _ => {
+            writer.write(0, 1);
+            writer.write(0b000001, 6); // verbatim
+            writer.write(0, 1);
+            for &sample in block {
+                writer.write_signed(i64::from(sample), 16);
+            }
+        },
+    }
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn rice_bits(values: &[u64], parameter: u32) -> u64 {
+    values.iter().map(|value| (value >> parameter) + 1 + u64::from(parameter)).sum()
+}
+
+// Frame numbers use the same variable-length coding as UTF-8
+fn utf8_number(value: u32) -> Vec<u8> {
+    if value < 0x80 {
+        return vec![value as u8];
+    }
+
+    let mut continuation = Vec::new();
+    let mut remaining = value;
+    // Payload bits that fit in the first byte shrink as continuation bytes are added
+    let mut first_byte_bits = 6;
+    while remaining >= 1 << first_byte_bits {
+        continuation.push(0x80 | (remaining & 0x3F) as u8);
+        remaining >>= 6;
+        first_byte_bits -= 1;
+    }
+
+    let marker = !(0xFFu8 >> (continuation.len() + 1));
+    let mut bytes = vec![marker | remaining as u8];
+    bytes.extend(continuation.into_iter().rev());
+    bytes
+}
+
+fn crc8(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |crc, &byte| {
+        (0..8).fold(crc ^ byte, |crc, _| {
+            if crc & 0x80 != 0 { (crc << 1) ^ 0x07 } else { crc << 1 }
+        })
+    })
+}
+
+fn crc16(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0u16, |crc, &byte| {
+        (0..8).fold(crc ^ (u16::from(byte) << 8), |crc, _| {
+            if crc & 0x8000 != 0 { (crc << 1) ^ 0x8005 } else { crc << 1 }
+        })
+    })
+}
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    // Bits waiting to fill the next byte, most significant first
+    pending: u64,
+    pending_bits: u32,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self {
+            bytes: Vec::new(),
+            pending: 0,
+            pending_bits: 0,
+        }
+    }
+
+    fn write(&mut self, value: u64, bits: u32) {
+        for bit in (0..bits).rev() {
+            self.pending = (self.pending << 1) | ((value >> bit) & 1);
+            self.pending_bits += 1;
+            if self.pending_bits == 8 {
+                self.bytes.push(self.pending as u8);
+                self.pending = 0;
+                self.pending_bits = 0;
+            }
+        }
+    }
+
+    fn write_signed(&mut self, value: i64, bits: u32) {
+        self.write(value as u64 & ((1 << bits) - 1), bits);
+    }
+
+    fn write_zeros(&mut self, count: u64) {
+        for _ in 0..count {
+            self.write(0, 1);
+        }
+    }
+
+    // Whole bytes written so far, used for the header checksum
+    fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    // Pads the last byte with zero bits
+    fn finish(mut self) -> Vec<u8> {
+        if self.pending_bits > 0 {
+            let padding = 8 - self.pending_bits;
+            self.write(0, padding);
+        }
+        self.bytes
+    }
+}
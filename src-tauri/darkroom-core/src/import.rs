@@ -0,0 +1,117 @@
+use crate::calculator::{CalculationEngine, CalculationError};
+use crate::export::EXPORT_FORMAT_VERSION;
+use crate::models::*;
+use std::fs;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ImportError {
+    #[error("Failed to read file: {0}")]
+    FileReadError(#[from] std::io::Error),
+    #[error("Not a DarkroomPro export: {0}")]
+    InvalidFile(#[from] serde_json::Error),
+    #[error("Export format version {0} is newer than this version of DarkroomPro reads (up to {EXPORT_FORMAT_VERSION})")]
+    UnsupportedVersion(u32),
+    #[error(transparent)]
+    CalculationError(#[from] CalculationError),
+}
+
+/// Reads a JSON file written by ExportManager::export_json
+pub fn read_export(file_path: &str) -> Result<ExportData, ImportError> {
+    parse_export(&fs::read_to_string(file_path)?)
+}
+
+pub fn parse_export(content: &str) -> Result<ExportData, ImportError> {
+    let data: ExportData = serde_json::from_str(content)?;
+    if data.version > EXPORT_FORMAT_VERSION {
+        return Err(ImportError::UnsupportedVersion(data.version));
+    }
+    Ok(data)
+}
+
+impl CalculationEngine {
+    /// Runs an imported calculation again against the current database, which
+    /// also validates it, and lists what came out differently
+    pub fn recalculate_export(&self, data: ExportData) -> Result<ImportedRecipe, CalculationError> {
+        let request = match &data.request {
+            Some(request) => request.clone(),
+            None => self.request_from_result(&data.calculation)?,
+        };
+        let recalculated = self.calculate_development(request.clone())?;
+        let differences = compare_results(&data.calculation, &recalculated);
+
+        Ok(ImportedRecipe {
+            request,
+            stored: data,
+            recalculated,
+            differences,
+        })
+    }
+
+    // Version 1 files only have the result, so find the film and developer by
+    // name. When a developer is listed with several dilutions, the one that
+    // gives the stored dilution wins.
+    fn request_from_result(&self, calculation: &CalculationResult) -> Result<CalculationRequest, CalculationError> {
+        let database = self.get_database()?;
+        let (film_key, film) = database.films
+            .iter()
+            .filter(|(_, film)| film.name == calculation.film_name)
+            .min_by_key(|(key, _)| key.as_str())
+            .ok_or_else(|| CalculationError::FilmNotFound(calculation.film_name.clone()))?;
+        let volume = calculation.developer_amount + calculation.water_amount;
+
+        let mut candidates: Vec<&String> = film.developers
+            .keys()
+            .filter(|key| {
+                self.find_developer(&database.developers, key)
+                    .is_ok_and(|developer| developer.name == calculation.developer_name)
+            })
+            .collect();
+        candidates.sort();
+        let developer_key = candidates
+            .iter()
+            .find(|key| {
+                self.calculate_dilution(&film.developers[key.as_str()].dilution, volume, &film.film_type)
+                    .is_ok_and(|(dilution, _, _)| dilution == calculation.dilution)
+            })
+            .or(candidates.first())
+            .ok_or_else(|| CalculationError::CombinationNotSupported {
+                film: calculation.film_name.clone(),
+                developer: calculation.developer_name.clone(),
+            })?;
+
+        Ok(CalculationRequest {
+            film_key: film_key.clone(),
+            developer_key: developer_key.to_string(),
+            temperature: calculation.temperature,
+            push_pull: calculation.push_pull,
+            volume,
+        })
+    }
+}
+
+fn compare_results(stored: &CalculationResult, recalculated: &CalculationResult) -> Vec<ResultDifference> {
+    let fields = [
+        ("time_minutes", stored.time_minutes.normalize().to_string(), recalculated.time_minutes.normalize().to_string()),
+        ("time_formatted", stored.time_formatted.clone(), recalculated.time_formatted.clone()),
+        ("dilution", stored.dilution.clone(), recalculated.dilution.clone()),
+        ("developer_amount", stored.developer_amount.to_string(), recalculated.developer_amount.to_string()),
+        ("water_amount", stored.water_amount.to_string(), recalculated.water_amount.to_string()),
+        ("temperature", stored.temperature.normalize().to_string(), recalculated.temperature.normalize().to_string()),
+        ("push_pull", stored.push_pull.to_string(), recalculated.push_pull.to_string()),
+        ("film_type", stored.film_type.label().to_string(), recalculated.film_type.label().to_string()),
+        ("film_name", stored.film_name.clone(), recalculated.film_name.clone()),
+        ("developer_name", stored.developer_name.clone(), recalculated.developer_name.clone()),
+        ("notes", stored.notes.join("; "), recalculated.notes.join("; ")),
+    ];
+
+    fields
+        .into_iter()
+        .filter(|(_, stored, recalculated)| stored != recalculated)
+        .map(|(field, stored, recalculated)| ResultDifference {
+            field: field.to_string(),
+            stored,
+            recalculated,
+        })
+        .collect()
+}
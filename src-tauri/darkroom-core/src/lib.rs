@@ -0,0 +1,120 @@
+//! Film development calculations, the film and developer catalogue, process
+//! timers and recipe exports behind DarkroomPro, usable from any Rust program.
+//!
+//! Load a database into a [`CalculationEngine`] and ask it for development
+//! times:
+//!
+//! ```no_run
+//! # #[cfg(feature = "serde")]
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use darkroom_core::{CalculationEngine, DatabaseManager, Decimal};
+//! use darkroom_core::models::CalculationRequest;
+//!
+//! let mut db_manager = DatabaseManager::new();
+//! db_manager.load_from_file("complete_database.json")?;
+//!
+//! let mut engine = CalculationEngine::new();
+//! engine.load_database(db_manager.take_database().expect("database was loaded"));
+//!
+//! let result = engine.calculate_development(CalculationRequest {
+//!     film_key: "ilford_hp5_plus".to_string(),
+//!     developer_key: "adox_rodinal_1_50".to_string(),
+//!     temperature: Decimal::from(20),
+//!     push_pull: 1,
+//!     volume: 500,
+//! })?;
+//! println!("{} at {}°C, {} dilution", result.time_formatted, result.temperature, result.dilution);
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "serde"))]
+//! # fn main() {}
+//! ```
+//!
+//! # Features
+//!
+//! - `serde` (default): `Serialize` and `Deserialize` on every model,
+//!   `DatabaseManager` for loading JSON databases, and process timers that
+//!   survive a restart. Without it a [`models::Database`] has to be built in code.
+//! - `export` (default): `ExportManager` and the PDF, CSV, JSON, Markdown,
+//!   HTML, template, calendar, cue, audio and QR code exports. Also builds the
+//!   `darkroom` command-line tool.
+//! - `tauri`: the `commands` the DarkroomPro desktop app registers with Tauri.
+//!   Nothing else in the crate depends on Tauri.
+
+/// Films, developers, requests and results, shared by every module.
+pub mod models;
+/// The calculation engine: development times, dilutions and catalogue lookups.
+pub mod calculator;
+/// Filtering, sorting and summarizing the film catalogue.
+pub mod catalogue;
+/// Loading and validating database files.
+#[cfg(feature = "serde")]
+pub mod database;
+/// Writing calculations, recipes and catalogues to files.
+#[cfg(feature = "export")]
+pub mod export;
+/// Where exports may be written.
+#[cfg(feature = "export")]
+pub mod export_files;
+/// Reading JSON exports back in.
+#[cfg(feature = "export")]
+pub mod import;
+/// Short recipe codes and their QR codes.
+pub mod share;
+/// CSV exports of calculations, comparisons and the catalogue.
+#[cfg(feature = "export")]
+pub mod csv;
+/// Markdown and HTML exports.
+#[cfg(feature = "export")]
+pub mod markup;
+/// User export templates.
+#[cfg(feature = "export")]
+pub mod template;
+/// iCalendar exports of sessions and chemistry expiry dates.
+#[cfg(feature = "export")]
+pub mod calendar;
+/// Process plans as timed cues.
+pub mod cues;
+/// Process plans as audio files of cue tones.
+#[cfg(feature = "export")]
+pub mod audio;
+/// FLAC encoding for audio exports.
+#[cfg(feature = "export")]
+pub mod flac;
+/// PDF reports, recipe cards and development charts.
+#[cfg(feature = "export")]
+pub mod pdf;
+/// Everything needed to develop one roll, on one card.
+pub mod recipe_card;
+/// Development times per developer, push/pull value and temperature for one film.
+pub mod chart;
+/// Searching films and developers by name.
+pub mod search;
+/// Ranking developers for a film against development goals.
+pub mod recommend;
+/// Replacements for discontinued films.
+pub mod substitute;
+/// Grouping a session's rolls into tanks.
+pub mod session;
+/// Step-by-step process plans.
+pub mod process;
+/// Staggering several tanks into one session.
+pub mod scheduler;
+/// Process timers with pour, agitate and drain cues.
+pub mod timer;
+/// Saving in-flight timers to disk.
+#[cfg(feature = "serde")]
+pub mod timer_store;
+/// Tauri commands and the state they expect the app to manage.
+#[cfg(feature = "tauri")]
+pub mod commands;
+#[cfg(all(test, feature = "serde"))]
+mod test_support;
+
+pub use calculator::{CalculationEngine, CalculationError};
+#[cfg(feature = "serde")]
+pub use database::{DatabaseError, DatabaseManager};
+#[cfg(feature = "export")]
+pub use export::{ExportError, ExportManager};
+// Temperatures and times are decimals, re-exported so callers use the same version
+pub use rust_decimal::Decimal;
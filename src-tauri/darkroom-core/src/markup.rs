@@ -0,0 +1,352 @@
+use crate::catalogue::{push_pull_label, PUBLISHED_PUSH_PULL};
+use crate::cues::clock;
+use crate::models::*;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+// Self-contained so the file can be mailed or printed without anything next to it
+const HTML_STYLE: &str = "
+body { font-family: Helvetica, Arial, sans-serif; color: #000; background: #fff; max-width: 48rem; margin: 2rem auto; padding: 0 1rem; font-size: 1.15rem; line-height: 1.45; }
+header { background: #000; color: #fff; padding: 0.8rem 1rem; }
+header h1 { margin: 0; font-size: 2rem; }
+header p { margin: 0.2rem 0 0; font-size: 1.2rem; }
+.time { font-size: 4rem; font-weight: bold; margin: 1rem 0 0; line-height: 1; }
+.conditions { font-size: 1.3rem; font-weight: bold; margin: 0.3rem 0 1rem; }
+h2 { font-size: 1.3rem; border-bottom: 3px solid #000; margin: 1.5rem 0 0.5rem; padding-bottom: 0.2rem; }
+table { width: 100%; border-collapse: collapse; }
+th, td { text-align: left; vertical-align: top; padding: 0.35rem 0.5rem 0.35rem 0; border-bottom: 1px solid #000; }
+td.value { font-weight: bold; }
+.safety { border: 3px solid #000; padding: 0.5rem 1rem; }
+.safety li { font-weight: bold; }
+footer { margin-top: 2rem; font-size: 0.85rem; }
+@media print { body { margin: 0; max-width: none; } header { -webkit-print-color-adjust: exact; print-color-adjust: exact; } }
+";
+
+pub fn recipe_markdown(card: &RecipeCard, timestamp: &str) -> String {
+    let calc = &card.calculation;
+    let mut content = format!(
+        "# {} in {}\n\n**{}** at {}\n\n",
+        markdown_text(&calc.film_name),
+        markdown_text(&calc.developer_name),
+        markdown_text(&calc.time_formatted),
+        markdown_text(&card.conditions().join(" · "))
+    );
+
+    content.push_str("## Chemistry\n\n| | |\n|---|---|\n");
+    for (label, value) in chemistry(calc) {
+        content.push_str(&format!("| {} | **{}** |\n", label, table_cell(&value)));
+    }
+
+    if let Some(plan) = &card.plan {
+        content.push_str("\n## Process\n\n| # | Step | Time | Temp | Agitation |\n|---|---|---|---|---|\n");
+        for (index, step) in plan.steps.iter().enumerate() {
+            content.push_str(&format!(
+                "| {} | {} | {} | {} | {} |\n",
+                index + 1,
+                table_cell(&step.name),
+                clock(step.duration_seconds),
+                temperature(step),
+                agitation(step)
+            ));
+        }
+    }
+
+    if !calc.notes.is_empty() {
+        content.push_str("\n## Notes\n\n");
+        for note in &calc.notes {
+            content.push_str(&format!("- {}\n", markdown_text(note)));
+        }
+    }
+
+    if !card.safety_warnings.is_empty() {
+        // Blank quote lines keep each warning its own paragraph
+        let warnings: Vec<String> = card
+            .safety_warnings
+            .iter()
+            .map(|warning| format!("> **Warning:** {}", markdown_text(warning)))
+            .collect();
+        content.push_str(&format!("\n## Safety\n\n{}\n", warnings.join("\n>\n")));
+    }
+
+    content.push_str(&format!("\n---\n\n_Generated by DarkroomPro on {}_\n", timestamp));
+    content
+}
+
+pub fn recipe_html(card: &RecipeCard, timestamp: &str) -> String {
+    let calc = &card.calculation;
+    let mut body = format!(
+        "<header>\n<h1>{}</h1>\n<p>{} · {}</p>\n</header>\n<p class=\"time\">{}</p>\n<p class=\"conditions\">{}</p>\n",
+        escape(&calc.film_name),
+        escape(&calc.developer_name),
+        escape(&calc.dilution),
+        escape(&calc.time_formatted),
+        escape(&card.conditions().join(" · "))
+    );
+
+    body.push_str("<h2>Chemistry</h2>\n<table>\n");
+    for (label, value) in chemistry(calc) {
+        body.push_str(&format!("<tr><th>{}</th><td class=\"value\">{}</td></tr>\n", label, escape(&value)));
+    }
+    body.push_str("</table>\n");
+
+    if let Some(plan) = &card.plan {
+        body.push_str("<h2>Process</h2>\n<table>\n<tr><th>#</th><th>Step</th><th>Time</th><th>Temp</th><th>Agitation</th></tr>\n");
+        for (index, step) in plan.steps.iter().enumerate() {
+            body.push_str(&format!(
+                "<tr><td>{}</td><td class=\"value\">{}</td><td class=\"value\">{}</td><td>{}</td><td>{}</td></tr>\n",
+                index + 1,
+                escape(&step.name),
+                clock(step.duration_seconds),
+                escape(&temperature(step)),
+                escape(&agitation(step))
+            ));
+        }
+        body.push_str("</table>\n");
+    }
+
+    if !calc.notes.is_empty() {
+        body.push_str("<h2>Notes</h2>\n<ul>\n");
+        for note in &calc.notes {
+            body.push_str(&format!("<li>{}</li>\n", escape(note)));
+        }
+        body.push_str("</ul>\n");
+    }
+
+    if !card.safety_warnings.is_empty() {
+        body.push_str("<h2>Safety</h2>\n<ul class=\"safety\">\n");
+        for warning in &card.safety_warnings {
+            body.push_str(&format!("<li>{}</li>\n", escape(warning)));
+        }
+        body.push_str("</ul>\n");
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{} in {}</title>\n<style>{}</style>\n</head>\n<body>\n{}<footer>Generated by DarkroomPro on {}</footer>\n</body>\n</html>\n",
+        escape(&calc.film_name),
+        escape(&calc.developer_name),
+        HTML_STYLE,
+        body,
+        escape(timestamp)
+    )
+}
+
+/// One section per film with a row per developer and a column per push/pull level
+pub fn database_summary_markdown(summary: &DatabaseSummary, timestamp: &str) -> String {
+    let mut content = format!(
+        "# DarkroomPro catalogue\n\nDatabase version {}, {} films, {} developers. Times are minutes:seconds at the datasheet temperature.\n",
+        summary.version,
+        summary.films.len(),
+        summary.developers.len()
+    );
+
+    let time_headers: Vec<String> = PUBLISHED_PUSH_PULL.iter().map(|&push_pull| push_pull_label(push_pull)).collect();
+    for entry in &summary.films {
+        let film = &entry.film;
+        content.push_str(&format!(
+            "\n## {}\n\n{}, ISO {}, {}{}\n\n",
+            markdown_text(&film.name),
+            markdown_text(&film.manufacturer),
+            film.iso,
+            film.film_type.label().to_lowercase(),
+            if film.current_production { "" } else { ", discontinued" }
+        ));
+        if entry.developments.is_empty() {
+            content.push_str("No development data.\n");
+            continue;
+        }
+
+        content.push_str(&format!(
+            "| Developer | Dilution | Temp | {} |\n|---|---|---|{}\n",
+            time_headers.join(" | "),
+            "---|".repeat(time_headers.len())
+        ));
+        for development in &entry.developments {
+            let times: Vec<String> = PUBLISHED_PUSH_PULL
+                .iter()
+                .map(|&push_pull| {
+                    development.push_pull_times
+                        .iter()
+                        .find(|time| time.push_pull == push_pull)
+                        .map(|time| minutes_clock(time.time_minutes))
+                        .unwrap_or_else(|| "–".to_string())
+                })
+                .collect();
+            content.push_str(&format!(
+                "| {} | {} | {}°C | {} |\n",
+                table_cell(&development.developer_name),
+                table_cell(&development.dilution),
+                development.temperature.normalize(),
+                times.join(" | ")
+            ));
+        }
+    }
+
+    if !summary.developers.is_empty() {
+        content.push_str("\n## Developers\n\n| Developer | Manufacturer | Type | Dilutions | Stock life | Working life |\n|---|---|---|---|---|---|\n");
+        for developer in &summary.developers {
+            content.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} |\n",
+                table_cell(&developer.name),
+                table_cell(&developer.manufacturer),
+                table_cell(&developer.developer_type),
+                table_cell(&developer.dilutions.join(", ")),
+                developer.stock_life_months.map(|months| format!("{} months", months)).unwrap_or_default(),
+                developer.working_life_hours.map(|hours| format!("{} hours", hours)).unwrap_or_default()
+            ));
+        }
+    }
+
+    content.push_str(&format!("\n---\n\n_Generated by DarkroomPro on {}_\n", timestamp));
+    content
+}
+
+// Wider than the recipe page, each chart prints on its own landscape sheet
+const CHART_STYLE: &str = "
+body { max-width: 64rem; font-size: 1rem; }
+section.chart { page-break-after: always; break-after: page; }
+section.chart:last-of-type { page-break-after: auto; break-after: auto; }
+td.time { font-weight: bold; }
+td.derived { font-weight: normal; font-style: italic; background: #e6e6e6; }
+td.missing { color: #666; }
+.legend { font-style: italic; }
+@media print { @page { size: A4 landscape; margin: 12mm; } td.derived { -webkit-print-color-adjust: exact; print-color-adjust: exact; } }
+";
+
+/// One section per film, derived times in italic with an asterisk and a grey
+/// background, so they are marked on screen and on a black and white printout
+pub fn development_charts_html(charts: &[DevelopmentChart], timestamp: &str) -> String {
+    let mut body = String::new();
+    for chart in charts {
+        let film = &chart.film;
+        body.push_str(&format!(
+            "<section class=\"chart\">\n<header>\n<h1>{}</h1>\n<p>{} · ISO {} · {}</p>\n</header>\n",
+            escape(&film.name),
+            escape(&film.manufacturer),
+            film.iso,
+            escape(film.film_type.label())
+        ));
+        if chart.rows.is_empty() {
+            body.push_str("<p>No development data.</p>\n</section>\n");
+            continue;
+        }
+
+        body.push_str("<h2>Push / pull</h2>\n<p>Minutes:seconds at the datasheet temperature.</p>\n<table>\n<tr><th>Developer</th><th>Dilution</th><th>Temp</th>");
+        for &push_pull in &chart.push_pull_values {
+            body.push_str(&format!("<th>{}</th>", push_pull_label(push_pull)));
+        }
+        body.push_str("</tr>\n");
+        for row in &chart.rows {
+            body.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}°C</td>{}</tr>\n",
+                escape(&row.developer_name),
+                escape(&row.dilution),
+                row.temperature.normalize(),
+                chart_cells(&row.push_pull)
+            ));
+        }
+        body.push_str("</table>\n");
+
+        if !chart.temperatures.is_empty() {
+            body.push_str("<h2>Temperature</h2>\n<p>Normal development, adjusted with the temperature compensation table.</p>\n<table>\n<tr><th>Developer</th><th>Dilution</th>");
+            for temperature in &chart.temperatures {
+                body.push_str(&format!("<th>{}°C</th>", temperature.normalize()));
+            }
+            body.push_str("</tr>\n");
+            for row in &chart.rows {
+                body.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td>{}</tr>\n",
+                    escape(&row.developer_name),
+                    escape(&row.dilution),
+                    chart_cells(&row.temperatures)
+                ));
+            }
+            body.push_str("</table>\n");
+        }
+
+        body.push_str("<p class=\"legend\">Bold times are from the datasheet. * Derived by DarkroomPro, not published: test before relying on it.</p>\n</section>\n");
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>Development charts</title>\n<style>{}{}</style>\n</head>\n<body>\n{}<footer>Generated by DarkroomPro on {}</footer>\n</body>\n</html>\n",
+        HTML_STYLE,
+        CHART_STYLE,
+        body,
+        escape(timestamp)
+    )
+}
+
+fn chart_cells(cells: &[ChartCell]) -> String {
+    cells
+        .iter()
+        .map(|cell| match (cell.time_minutes, cell.derived) {
+            (None, _) => "<td class=\"missing\">–</td>".to_string(),
+            (Some(time), false) => format!("<td class=\"time\">{}</td>", minutes_clock(time)),
+            (Some(time), true) => format!("<td class=\"derived\" title=\"Derived, not in the datasheet\">{}*</td>", minutes_clock(time)),
+        })
+        .collect()
+}
+
+/// Datasheet minutes as the timer shows them, e.g. 7.5 as 7:30
+pub fn minutes_clock(minutes: Decimal) -> String {
+    clock((minutes * Decimal::from(60)).round().to_u32().unwrap_or(0))
+}
+
+fn chemistry(calc: &CalculationResult) -> [(&'static str, String); 4] {
+    [
+        ("Dilution", calc.dilution.clone()),
+        ("Developer", format!("{} ml", calc.developer_amount)),
+        ("Water", format!("{} ml", calc.water_amount)),
+        ("Total", format!("{} ml", calc.developer_amount + calc.water_amount)),
+    ]
+}
+
+fn temperature(step: &ProcessStep) -> String {
+    step.temperature.map(|t| format!("{}°C", t.normalize())).unwrap_or_default()
+}
+
+fn agitation(step: &ProcessStep) -> String {
+    step.agitation.as_ref().map(|a| a.describe()).unwrap_or_else(|| "None".to_string())
+}
+
+// A pipe would end the cell early
+fn table_cell(text: &str) -> String {
+    markdown_text(text).replace('|', "\\|")
+}
+
+// Text from the database shown as-is, so a name like "*Special* Pan" or "#1 Developer"
+// is not read as emphasis, a link or a heading
+fn markdown_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        if matches!(character, '\\' | '*' | '_' | '[' | '`') {
+            escaped.push('\\');
+        }
+        escaped.push(character);
+    }
+    if escaped.starts_with('#') {
+        escaped.insert(0, '\\');
+    }
+    escaped
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_metacharacters_are_shown_as_typed() {
+        assert_eq!(markdown_text("*Special* Pan_100 [test] `x` a\\b"), "\\*Special\\* Pan\\_100 \\[test] \\`x\\` a\\\\b");
+        assert_eq!(markdown_text("#1 Developer"), "\\#1 Developer");
+        assert_eq!(markdown_text("Developer #1"), "Developer #1");
+        assert_eq!(table_cell("1+1 | *stock*"), "1+1 \\| \\*stock\\*");
+        assert_eq!(markdown_text("Ilford HP5 Plus"), "Ilford HP5 Plus");
+    }
+}
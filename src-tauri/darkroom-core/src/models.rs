@@ -0,0 +1,1131 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::HashMap;
+use rust_decimal::Decimal;
+
+/// Shown in place of a dilution for chemistry used as supplied
+pub const READY_TO_USE: &str = "Ready to use";
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Film {
+    pub name: String,
+    pub manufacturer: String,
+    pub iso: u32,
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub film_type: FilmType,
+    pub process: String,
+    pub year_released: Option<u32>,
+    pub current_production: bool,
+    pub price_35mm_usd: Option<Decimal>,
+    pub alternative_names: Vec<String>,
+    pub description: String,
+    pub grain: String,
+    pub contrast: String,
+    pub best_uses: Vec<String>,
+    pub developers: HashMap<String, DeveloperData>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Developer {
+    pub name: String,
+    pub manufacturer: String,
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub developer_type: String,
+    pub year_introduced: Option<u32>,
+    pub price_per_liter_usd: Option<Decimal>,
+    pub capacity_rolls_per_liter: Option<u32>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub alternative_names: Vec<String>,
+    pub description: String,
+    pub characteristics: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub dilutions: HashMap<String, DilutionInfo>,
+    pub stock_life_months: Option<u32>,
+    pub working_life_hours: Option<u32>,
+    pub safety_notes: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub best_for: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub avoid_with: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DilutionInfo {
+    pub ratio: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DeveloperData {
+    /// Color process entries have no dilution, they are used ready to use
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub dilution: String,
+    /// Normal development time for black and white films
+    pub time_minutes: Option<Decimal>,
+    /// Used when time_minutes is missing
+    pub time: Option<Decimal>,
+    /// Datasheet temperature of the black and white times
+    pub temperature_c: Option<Decimal>,
+    /// Datasheet temperature of the color negative developer
+    pub developer_temp_c: Option<Decimal>,
+    /// Datasheet temperature of the slide first developer
+    pub first_dev_temp_c: Option<Decimal>,
+    /// Continuous agitation at the start of development
+    pub agitation_initial_seconds: u32,
+    /// Length of each later agitation
+    pub agitation_interval_seconds: u32,
+    /// Period between later agitations
+    pub agitation_frequency_minutes: Decimal,
+    
+    // Push/Pull times for B&W
+    pub push_1_stop_minutes: Option<Decimal>,
+    pub push_2_stop_minutes: Option<Decimal>,
+    pub push_3_stop_minutes: Option<Decimal>,
+    pub pull_1_stop_minutes: Option<Decimal>,
+    pub pull_2_stop_minutes: Option<Decimal>,
+    
+    // Color negative specific
+    pub developer_time_minutes: Option<Decimal>,
+    pub push_1_stop_dev_time: Option<Decimal>,
+    pub push_2_stop_dev_time: Option<Decimal>,
+    pub pull_1_stop_dev_time: Option<Decimal>,
+    
+    // Color process steps after the developer
+    pub bleach_time_minutes: Option<Decimal>,
+    pub bleach_temp_c: Option<Decimal>,
+    pub fixer_time_minutes: Option<Decimal>,
+    pub fixer_temp_c: Option<Decimal>,
+    pub stabilizer_time_minutes: Option<Decimal>,
+    pub stabilizer_temp_c: Option<Decimal>,
+    pub temperature_tolerance_c: Option<Decimal>,
+    
+    // Slide film specific
+    pub first_dev_time_minutes: Option<Decimal>,
+    pub push_1_stop_first_dev_time: Option<Decimal>,
+    pub push_2_stop_first_dev_time: Option<Decimal>,
+    pub pull_1_stop_first_dev_time: Option<Decimal>,
+    pub reversal_time_minutes: Option<Decimal>,
+    pub reversal_temp_c: Option<Decimal>,
+    pub color_dev_time_minutes: Option<Decimal>,
+    pub color_dev_temp_c: Option<Decimal>,
+    
+    // Additional fields
+    pub dilution_ratio: Option<String>,
+    pub special_notes: Option<String>,
+}
+
+impl DeveloperData {
+    /// The dilution as shown to the user, "Ready to use" when there is none
+    pub fn display_dilution(&self) -> String {
+        if self.dilution.is_empty() {
+            READY_TO_USE.to_string()
+        } else {
+            self.dilution.clone()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum FilmType {
+    BlackWhite,
+    ColorNegative,
+    Slide,
+}
+
+impl FilmType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FilmType::BlackWhite => "black_white",
+            FilmType::ColorNegative => "color_negative",
+            FilmType::Slide => "slide",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FilmType::BlackWhite => "Black & white",
+            FilmType::ColorNegative => "Color negative",
+            FilmType::Slide => "Slide",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Database {
+    pub films: HashMap<String, Film>,
+    pub developers: HashMap<String, Developer>,
+    pub temperature_compensation: HashMap<String, Decimal>,
+    pub metadata: DatabaseMetadata,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DatabaseMetadata {
+    pub version: String,
+    pub last_updated: String,
+    pub film_count: u32,
+    pub developer_count: u32,
+    pub total_combinations: u32,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub notes: DatabaseNotes,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct DatabaseNotes {
+    /// Free text entries such as "Fuji Neopan 400 (discontinued 2009)"
+    pub discontinued_films: Vec<String>,
+    pub pricing_note: Option<String>,
+    pub development_note: Option<String>,
+    pub safety_note: Option<String>,
+    pub testing_note: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CalculationRequest {
+    pub film_key: String,
+    pub developer_key: String,
+    pub temperature: Decimal,
+    pub push_pull: i32,
+    pub volume: u32,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CalculationResult {
+    pub time_minutes: Decimal,
+    pub time_formatted: String,
+    pub dilution: String,
+    pub developer_amount: u32,
+    pub water_amount: u32,
+    pub temperature: Decimal,
+    pub push_pull: i32,
+    pub film_type: FilmType,
+    pub film_name: String,
+    pub developer_name: String,
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DilutionRatio {
+    pub developer: u32,
+    pub water: u32,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ExportData {
+    /// See export::EXPORT_FORMAT_VERSION, files written before it existed are version 1
+    #[cfg_attr(feature = "serde", serde(default = "first_export_version"))]
+    pub version: u32,
+    /// The inputs, so an imported file can be recalculated
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub request: Option<CalculationRequest>,
+    pub calculation: CalculationResult,
+    /// Only present when a full recipe is exported
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub plan: Option<ProcessPlan>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub safety_warnings: Vec<String>,
+    pub timestamp: String,
+    pub format: ExportFormat,
+}
+
+#[cfg(feature = "serde")]
+fn first_export_version() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ShareCode {
+    /// e.g. "DRP1-GIXDA7DJ...", see share::encode_share_code
+    pub code: String,
+    pub database_version: String,
+    pub film_name: String,
+    pub developer_name: String,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SharedRecipe {
+    pub request: CalculationRequest,
+    /// Database version the code was made with
+    pub database_version: String,
+    pub calculation: CalculationResult,
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum QrFormat {
+    Svg,
+    Png,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ResultDifference {
+    /// Field of CalculationResult, e.g. "time_minutes"
+    pub field: String,
+    pub stored: String,
+    pub recalculated: String,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ImportedRecipe {
+    /// Inputs used for the recalculation, worked out from the names for version 1 files
+    pub request: CalculationRequest,
+    pub stored: ExportData,
+    pub recalculated: CalculationResult,
+    /// Empty when the current database gives the same result
+    pub differences: Vec<ResultDifference>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum ExportFormat {
+    Pdf,
+    Csv,
+    Json,
+    Markdown,
+    Html,
+    // A user template from the templates folder, by name
+    Template(String),
+}
+
+impl ExportFormat {
+    /// For generated file names. Templates name their own in their file name.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Pdf => "pdf",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+            ExportFormat::Template(_) => "txt",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ExportTemplate {
+    /// The file it produces without the template suffix, e.g. "club-card.md" for club-card.md.j2
+    pub name: String,
+    pub file_name: String,
+    /// Extension of the files it produces, e.g. "md" for club-card.md.j2
+    pub extension: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CsvOptions {
+    /// Comma when not given, European spreadsheets usually expect a semicolon
+    pub delimiter: Option<char>,
+    /// Point when not given
+    pub decimal_separator: Option<char>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CatalogueKind {
+    Film,
+    Developer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SearchField {
+    Name,
+    AlternativeName,
+    Manufacturer,
+    Key,
+    Iso,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HighlightRange {
+    /// Character offset into the matched value
+    pub start: usize,
+    /// Character offset just past the match
+    pub end: usize,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SearchMatch {
+    pub field: SearchField,
+    pub value: String,
+    pub highlights: Vec<HighlightRange>,
+    pub typos: u32,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SearchResult {
+    pub kind: CatalogueKind,
+    pub key: String,
+    pub name: String,
+    pub manufacturer: String,
+    pub score: u32,
+    pub matches: Vec<SearchMatch>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum FilmSortKey {
+    #[default]
+    Name,
+    Iso,
+    Manufacturer,
+    YearReleased,
+    Price,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct FilmQuery {
+    pub film_types: Vec<FilmType>,
+    pub iso_min: Option<u32>,
+    pub iso_max: Option<u32>,
+    pub manufacturers: Vec<String>,
+    pub current_production: Option<bool>,
+    pub grain: Vec<String>,
+    pub contrast: Vec<String>,
+    /// A film must list every requested use
+    pub best_uses: Vec<String>,
+    pub sort_by: FilmSortKey,
+    pub descending: bool,
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FilmSummary {
+    pub key: String,
+    pub name: String,
+    pub manufacturer: String,
+    pub iso: u32,
+    pub film_type: FilmType,
+    pub year_released: Option<u32>,
+    pub current_production: bool,
+    pub price_35mm_usd: Option<Decimal>,
+    pub grain: String,
+    pub contrast: String,
+    pub best_uses: Vec<String>,
+    pub developer_count: usize,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FacetCount {
+    pub value: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FilmFacets {
+    pub film_types: Vec<FacetCount>,
+    pub iso: Vec<FacetCount>,
+    pub manufacturers: Vec<FacetCount>,
+    pub current_production: Vec<FacetCount>,
+    pub grain: Vec<FacetCount>,
+    pub contrast: Vec<FacetCount>,
+    pub best_uses: Vec<FacetCount>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FilmQueryResult {
+    pub total: usize,
+    pub offset: usize,
+    pub films: Vec<FilmSummary>,
+    pub facets: FilmFacets,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DevelopmentGoal {
+    FinestGrain,
+    MaximumAcutance,
+    PushProcessing,
+    LowestCost,
+    LongestShelfLife,
+    InInventory,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RecommendationRequest {
+    pub film_key: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub goals: Vec<DevelopmentGoal>,
+    /// Intended push (positive) or pull (negative) in stops
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub push_pull: i32,
+    /// Developer keys the user has on hand
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub inventory: Vec<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RecommendationReason {
+    pub goal: Option<DevelopmentGoal>,
+    pub points: i32,
+    pub explanation: String,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DeveloperRecommendation {
+    pub developer_key: String,
+    pub developer_name: String,
+    pub dilution: String,
+    pub score: u32,
+    /// Base time at 20°C for the requested push/pull, None when the data does not cover it
+    pub time_minutes: Option<Decimal>,
+    pub cost_per_roll_usd: Option<Decimal>,
+    pub in_inventory: bool,
+    pub reasons: Vec<RecommendationReason>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FilmSubstitute {
+    pub film: FilmSummary,
+    pub developer_key: String,
+    pub similarity: u32,
+    pub reasons: Vec<String>,
+    /// Push/pull that keeps the original exposure index on the substitute
+    pub push_pull: i32,
+    pub starting_recipe: Option<CalculationResult>,
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SubstitutionResult {
+    pub film_key: String,
+    pub film_name: String,
+    pub discontinued: bool,
+    pub discontinued_note: Option<String>,
+    pub original_recipe: CalculationResult,
+    pub substitutes: Vec<FilmSubstitute>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PushPullTime {
+    pub push_pull: i32,
+    pub time_minutes: Decimal,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DeveloperFilmEntry {
+    pub film: FilmSummary,
+    pub developer_key: String,
+    pub dilution: String,
+    /// Datasheet temperature; black and white times are converted to 20°C
+    pub temperature: Decimal,
+    pub time_minutes: Option<Decimal>,
+    /// Only the push/pull levels with published times
+    pub push_pull_times: Vec<PushPullTime>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CatalogueDevelopment {
+    pub developer_key: String,
+    pub developer_name: String,
+    pub dilution: String,
+    /// Datasheet temperature, the times are as published for it
+    pub temperature: Decimal,
+    /// Only the push/pull levels with published times
+    pub push_pull_times: Vec<PushPullTime>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CatalogueFilm {
+    pub film: FilmSummary,
+    pub developments: Vec<CatalogueDevelopment>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CatalogueDeveloper {
+    pub key: String,
+    pub name: String,
+    pub manufacturer: String,
+    pub developer_type: String,
+    pub dilutions: Vec<String>,
+    pub stock_life_months: Option<u32>,
+    pub working_life_hours: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DatabaseSummary {
+    pub version: String,
+    /// Films matching the query in its sort order, ignoring offset and limit
+    pub films: Vec<CatalogueFilm>,
+    /// Every developer used by those films
+    pub developers: Vec<CatalogueDeveloper>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ChartCell {
+    /// None where the engine has no time, e.g. push 3 for color films
+    pub time_minutes: Option<Decimal>,
+    /// Calculated by the engine rather than published in the datasheet
+    pub derived: bool,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ChartRow {
+    pub developer_key: String,
+    pub developer_name: String,
+    pub dilution: String,
+    /// Datasheet temperature, the push/pull times are for it
+    pub temperature: Decimal,
+    /// One cell per DevelopmentChart::push_pull_values
+    pub push_pull: Vec<ChartCell>,
+    /// Normal development at each DevelopmentChart::temperatures
+    pub temperatures: Vec<ChartCell>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DevelopmentChart {
+    pub film: FilmSummary,
+    pub push_pull_values: Vec<i32>,
+    /// Empty for color and slide films, which are processed at a fixed temperature
+    pub temperatures: Vec<Decimal>,
+    pub rows: Vec<ChartRow>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ComparisonRequest {
+    pub film_key: String,
+    /// Every developer with data for the film when empty
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub developer_keys: Vec<String>,
+    /// The datasheet temperature of each developer when empty
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub temperatures: Vec<Decimal>,
+    /// Normal development only when empty
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub push_pull_values: Vec<i32>,
+    pub volume: u32,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CalculationFailure {
+    pub code: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ComparisonCell {
+    pub developer_key: String,
+    pub temperature: Decimal,
+    pub push_pull: i32,
+    pub result: Option<CalculationResult>,
+    pub error: Option<CalculationFailure>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ComparisonMatrix {
+    pub film_key: String,
+    pub film_name: String,
+    pub developer_keys: Vec<String>,
+    pub temperatures: Vec<Decimal>,
+    pub push_pull_values: Vec<i32>,
+    /// Ordered by developer, then temperature, then push/pull
+    pub cells: Vec<ComparisonCell>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FilmFormat {
+    #[default]
+    #[cfg_attr(feature = "serde", serde(rename = "35mm"))]
+    Film35mm,
+    #[cfg_attr(feature = "serde", serde(rename = "120"))]
+    Film120,
+    #[cfg_attr(feature = "serde", serde(rename = "220"))]
+    Film220,
+}
+
+impl FilmFormat {
+    /// Working solution for one reel in a typical small tank
+    pub fn solution_volume_ml(&self) -> u32 {
+        match self {
+            FilmFormat::Film35mm => 300,
+            FilmFormat::Film120 | FilmFormat::Film220 => 500,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SessionRoll {
+    pub label: Option<String>,
+    pub film_key: String,
+    pub exposure_index: u32,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub format: FilmFormat,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SessionRequest {
+    pub rolls: Vec<SessionRoll>,
+    /// A developer, or one of its dilutions such as "kodak_hc110_b"
+    pub developer_key: String,
+    pub temperature: Decimal,
+    pub time_tolerance_seconds: Option<u32>,
+    pub max_rolls_per_tank: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SessionRollResult {
+    pub roll_index: usize,
+    pub label: String,
+    pub film_key: String,
+    pub film_name: String,
+    pub exposure_index: u32,
+    pub format: FilmFormat,
+    pub push_pull: i32,
+    pub time_minutes: Decimal,
+    pub volume_ml: u32,
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SessionTank {
+    pub tank_number: usize,
+    pub developer_key: String,
+    pub developer_name: String,
+    pub dilution: String,
+    pub rolls: Vec<SessionRollResult>,
+    pub time_minutes: Decimal,
+    pub time_formatted: String,
+    pub volume_ml: u32,
+    pub developer_amount_ml: u32,
+    pub water_amount_ml: u32,
+    /// Largest difference between the tank time and a roll's own time
+    pub max_deviation_seconds: u32,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FlaggedRoll {
+    pub roll_index: usize,
+    pub label: String,
+    pub film_key: String,
+    pub tank_number: Option<usize>,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ConcentrateTotal {
+    pub developer_name: String,
+    pub dilution: String,
+    pub amount_ml: u32,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SessionPlan {
+    pub temperature: Decimal,
+    pub tanks: Vec<SessionTank>,
+    pub flagged_rolls: Vec<FlaggedRoll>,
+    pub concentrate_totals: Vec<ConcentrateTotal>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ProcessStepKind {
+    PreSoak,
+    Developer,
+    FirstDeveloper,
+    Reversal,
+    ColorDeveloper,
+    StopBath,
+    Bleach,
+    Fixer,
+    Wash,
+    Stabilizer,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AgitationSchedule {
+    pub initial_seconds: u32,
+    /// Length of each later agitation
+    pub duration_seconds: u32,
+    /// Period between later agitations, 0 for stand development
+    pub every_seconds: u32,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ProcessStep {
+    pub kind: ProcessStepKind,
+    pub name: String,
+    /// From the start of the pour to the end of the drain
+    pub duration_seconds: u32,
+    pub temperature: Option<Decimal>,
+    pub agitation: Option<AgitationSchedule>,
+    pub pour_seconds: u32,
+    pub drain_seconds: u32,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ProcessPlan {
+    pub name: String,
+    pub steps: Vec<ProcessStep>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ProcessPlanRequest {
+    pub calculation: CalculationRequest,
+    pub label: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub pre_soak_seconds: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ProcessActionKind {
+    Pour,
+    Agitate,
+    Drain,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ProcessAction {
+    pub kind: ProcessActionKind,
+    pub step_index: usize,
+    /// From the start of the plan
+    pub start_seconds: u32,
+    pub duration_seconds: u32,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScheduleRequest {
+    pub plans: Vec<ProcessPlan>,
+    /// Minimum time between pours or drains in different tanks
+    pub min_pour_gap_seconds: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScheduledStep {
+    pub step_index: usize,
+    pub kind: ProcessStepKind,
+    pub name: String,
+    pub start_seconds: u32,
+    pub end_seconds: u32,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScheduledTank {
+    pub plan_index: usize,
+    pub name: String,
+    pub start_seconds: u32,
+    pub end_seconds: u32,
+    pub steps: Vec<ScheduledStep>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScheduledAction {
+    pub plan_index: usize,
+    pub tank_name: String,
+    pub step_name: String,
+    pub action: ProcessAction,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SessionSchedule {
+    pub total_seconds: u32,
+    pub min_pour_gap_seconds: u32,
+    pub tanks: Vec<ScheduledTank>,
+    /// Every hands-on action across all tanks, in time order
+    pub actions: Vec<ScheduledAction>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum CalendarEvents {
+    // One event for every step of every tank
+    #[default]
+    Steps,
+    // One event per tank, from its first pour to its last drain
+    Tanks,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ChemistryBatch {
+    /// Shown on the calendar, the developer name when not set
+    pub label: Option<String>,
+    pub developer_key: String,
+    pub mixed_on: NaiveDate,
+    /// Diluted working solution rather than stock
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub working_solution: bool,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BatchExpiry {
+    pub label: String,
+    pub developer_name: String,
+    pub mixed_on: NaiveDate,
+    pub expires_on: NaiveDate,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CalendarRequest {
+    /// Calendar and event title prefix, e.g. "Saturday session"
+    pub name: Option<String>,
+    pub start: DateTime<Utc>,
+    /// A session staggered by schedule_session, or a single process plan
+    pub schedule: Option<SessionSchedule>,
+    pub plan: Option<ProcessPlan>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub events: CalendarEvents,
+    /// Reminder before each pour and agitation, 10 seconds when not set
+    pub reminder_seconds: Option<u32>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub batches: Vec<ChemistryBatch>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TimerRequest {
+    /// Identifies the timer in commands and events, the plan name when not set
+    pub name: Option<String>,
+    pub plan: ProcessPlan,
+    /// Warning given before each pour and drain, 10 seconds when not set
+    pub lead_in_seconds: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TimerState {
+    Running,
+    Paused,
+    Completed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TimerStatus {
+    pub timer_name: String,
+    pub plan_name: String,
+    pub state: TimerState,
+    pub phase_index: usize,
+    pub phase_kind: ProcessStepKind,
+    pub phase_name: String,
+    pub phase_elapsed_seconds: u32,
+    pub phase_remaining_seconds: u32,
+    pub elapsed_seconds: u32,
+    pub remaining_seconds: u32,
+    pub total_seconds: u32,
+    pub next_action: Option<ProcessAction>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TimerEventKind {
+    Started,
+    Tick,
+    PhaseChanged,
+    // An upcoming pour or drain, sent lead_in_seconds before it is due
+    LeadIn,
+    ActionDue,
+    Paused,
+    Resumed,
+    Skipped,
+    Adjusted,
+    Completed,
+    Cancelled,
+    Reattached,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TimerEvent {
+    pub kind: TimerEventKind,
+    pub action: Option<ProcessAction>,
+    pub status: TimerStatus,
+}
+
+/// A running or paused timer as saved to disk, so it survives a restart or crash
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TimerSnapshot {
+    /// Missing from files saved before timers were named
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub name: String,
+    pub plan: ProcessPlan,
+    pub lead_in_seconds: u32,
+    pub state: TimerState,
+    pub started_at: DateTime<Utc>,
+    pub paused_at: Option<DateTime<Utc>>,
+    pub saved_at: DateTime<Utc>,
+    /// Process time when the snapshot was saved
+    pub elapsed_ms: u64,
+    pub phase_index: usize,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InFlightTimer {
+    pub started_at: DateTime<Utc>,
+    pub paused_at: Option<DateTime<Utc>>,
+    /// Restored after a restart and not reattached yet
+    pub recovered: bool,
+    pub plan: ProcessPlan,
+    pub status: TimerStatus,
+}
+
+/// One entry in the combined queue of what to do next across all timers
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UpcomingAction {
+    pub timer_name: String,
+    pub step_name: String,
+    pub action: ProcessAction,
+    /// Counted from now, or from resuming when the timer is paused
+    pub due_in_seconds: u32,
+    pub paused: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum CueFormat {
+    WebVtt,
+    Srt,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum AudioFormat {
+    Wav,
+    Flac,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CueKind {
+    Pour,
+    Agitate,
+    Drain,
+    // Standing time between hands-on actions
+    Wait,
+    Complete,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Cue {
+    pub index: usize,
+    pub kind: CueKind,
+    pub step_index: usize,
+    pub start_seconds: u32,
+    pub end_seconds: u32,
+    pub title: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CueList {
+    pub name: String,
+    pub total_seconds: u32,
+    pub cues: Vec<Cue>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum PageSize {
+    #[default]
+    A4,
+    Letter,
+    A6,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RecipeCardRequest {
+    pub recipe: ProcessPlanRequest,
+    /// Only used for PDF
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub page_size: PageSize,
+}
+
+/// Everything printed on a recipe card
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RecipeCard {
+    pub calculation: CalculationResult,
+    pub plan: Option<ProcessPlan>,
+    pub safety_warnings: Vec<String>,
+}
@@ -0,0 +1,596 @@
+use crate::catalogue::{push_pull_label, PUBLISHED_PUSH_PULL};
+use crate::cues::clock;
+use crate::export::{ExportError, ExportManager};
+use crate::markup::minutes_clock;
+use crate::models::*;
+use printpdf::path::PaintMode;
+use printpdf::{
+    BuiltinFont, Color, Greyscale, IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference,
+    Point, Rect,
+};
+
+const MM_PER_POINT: f32 = 0.352_778;
+// Extra space under each line of text, as a share of the font size
+const LINE_SPACING: f32 = 0.35;
+
+// Page geometry and type sizes, large enough to read at arm's length under a safelight
+struct CardStyle {
+    width: f32,
+    height: f32,
+    margin: f32,
+    title: f32,
+    time: f32,
+    heading: f32,
+    body: f32,
+    footer: f32,
+    rule: f32,
+}
+
+impl CardStyle {
+    fn for_page(page_size: PageSize) -> Self {
+        match page_size {
+            PageSize::A4 => Self::full_page(210.0, 297.0),
+            PageSize::Letter => Self::full_page(215.9, 279.4),
+            PageSize::A6 => CardStyle {
+                width: 105.0,
+                height: 148.0,
+                margin: 6.0,
+                title: 14.0,
+                time: 30.0,
+                heading: 10.0,
+                body: 8.5,
+                footer: 6.0,
+                rule: 1.5,
+            },
+        }
+    }
+
+    // Dense A4 layout for the catalogue tables, which are read at a desk
+    fn table() -> Self {
+        CardStyle {
+            width: 210.0,
+            height: 297.0,
+            margin: 12.0,
+            title: 18.0,
+            time: 18.0,
+            heading: 11.0,
+            body: 8.0,
+            footer: 7.0,
+            rule: 1.0,
+        }
+    }
+
+    // Landscape A4 for the wall charts, one film per page
+    fn chart() -> Self {
+        CardStyle {
+            width: 297.0,
+            height: 210.0,
+            margin: 12.0,
+            title: 22.0,
+            time: 22.0,
+            heading: 13.0,
+            body: 10.5,
+            footer: 7.0,
+            rule: 1.5,
+        }
+    }
+
+    fn full_page(width: f32, height: f32) -> Self {
+        CardStyle {
+            width,
+            height,
+            margin: 15.0,
+            title: 26.0,
+            time: 60.0,
+            heading: 16.0,
+            body: 13.0,
+            footer: 9.0,
+            rule: 2.5,
+        }
+    }
+}
+
+impl ExportManager {
+    pub fn export_recipe_card(
+        &self,
+        card: &RecipeCard,
+        page_size: PageSize,
+        file_path: Option<String>,
+    ) -> Result<String, ExportError> {
+        let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string();
+        let content = render_recipe_card(card, page_size, &timestamp)?;
+        let name = [card.calculation.film_name.as_str(), &card.calculation.developer_name];
+        let path = self.write_file(file_path.as_deref(), &name, "pdf", &content)?;
+        Ok(format!("Exported recipe card to PDF file: {}", path))
+    }
+}
+
+pub fn render_recipe_card(card: &RecipeCard, page_size: PageSize, timestamp: &str) -> Result<Vec<u8>, ExportError> {
+    let calc = &card.calculation;
+    let mut writer = CardWriter::new(&calc.film_name, CardStyle::for_page(page_size))?;
+    let style_body = writer.style.body;
+
+    writer.title_band(&calc.film_name, &format!("{}  ·  {}", calc.developer_name, calc.dilution));
+
+    writer.development_time(&calc.time_formatted, &card.conditions().join("  ·  "));
+
+    writer.heading("CHEMISTRY");
+    writer.columns(&[
+        (0.0, "Dilution".to_string(), Face::Regular),
+        (0.45, calc.dilution.clone(), Face::Bold),
+    ]);
+    writer.columns(&[
+        (0.0, "Developer".to_string(), Face::Regular),
+        (0.45, format!("{} ml", calc.developer_amount), Face::Bold),
+    ]);
+    writer.columns(&[
+        (0.0, "Water".to_string(), Face::Regular),
+        (0.45, format!("{} ml", calc.water_amount), Face::Bold),
+    ]);
+    writer.columns(&[
+        (0.0, "Total".to_string(), Face::Regular),
+        (0.45, format!("{} ml", calc.developer_amount + calc.water_amount), Face::Bold),
+    ]);
+
+    if let Some(plan) = &card.plan {
+        writer.heading("PROCESS");
+        writer.columns(&[
+            (0.0, "Step".to_string(), Face::Bold),
+            (0.34, "Time".to_string(), Face::Bold),
+            (0.5, "Temp".to_string(), Face::Bold),
+            (0.64, "Agitation".to_string(), Face::Bold),
+        ]);
+        writer.rule(0.5);
+        for step in &plan.steps {
+            writer.columns(&[
+                (0.0, step.name.clone(), Face::Bold),
+                (0.34, clock(step.duration_seconds), Face::Bold),
+                (0.5, step.temperature.map(|t| format!("{}°C", t.normalize())).unwrap_or_default(), Face::Regular),
+                (0.64, step.agitation.as_ref().map(|a| a.describe()).unwrap_or_else(|| "None".to_string()), Face::Regular),
+            ]);
+            writer.rule(0.3);
+        }
+    }
+
+    if !calc.notes.is_empty() {
+        writer.heading("NOTES");
+        for note in &calc.notes {
+            writer.paragraph(&format!("- {}", note), style_body, Face::Regular);
+        }
+    }
+
+    if !card.safety_warnings.is_empty() {
+        writer.heading("SAFETY");
+        let top = writer.y;
+        for warning in &card.safety_warnings {
+            writer.paragraph(&format!("! {}", warning), style_body, Face::Bold);
+        }
+        writer.frame(top);
+    }
+
+    writer.footer(&format!("DarkroomPro · {}", timestamp));
+    writer.finish()
+}
+
+// Column positions as a share of the content width: developer, dilution,
+// temperature, then one per push/pull level
+const SUMMARY_COLUMNS: [f32; 9] = [0.0, 0.3, 0.47, 0.55, 0.625, 0.7, 0.775, 0.85, 0.925];
+
+pub fn render_database_summary(summary: &DatabaseSummary, timestamp: &str) -> Result<Vec<u8>, ExportError> {
+    let mut writer = CardWriter::new("DarkroomPro catalogue", CardStyle::table())?;
+    let body = writer.style.body;
+
+    writer.title_band(
+        "DarkroomPro catalogue",
+        &format!(
+            "Database {}  ·  {} films  ·  {} developers",
+            summary.version,
+            summary.films.len(),
+            summary.developers.len()
+        ),
+    );
+    writer.paragraph("Times are minutes:seconds at the datasheet temperature.", body, Face::Regular);
+
+    let mut headers = vec!["Developer".to_string(), "Dilution".to_string(), "Temp".to_string()];
+    headers.extend(PUBLISHED_PUSH_PULL.iter().map(|&push_pull| push_pull_label(push_pull)));
+
+    for entry in &summary.films {
+        let film = &entry.film;
+        writer.heading(&format!(
+            "{}  ·  ISO {}  ·  {}{}",
+            film.name,
+            film.iso,
+            film.film_type.label(),
+            if film.current_production { "" } else { "  ·  discontinued" }
+        ));
+        if entry.developments.is_empty() {
+            writer.paragraph("No development data.", body, Face::Regular);
+            continue;
+        }
+
+        writer.columns(&row(&SUMMARY_COLUMNS, &headers, Face::Bold));
+        writer.rule(0.5);
+        for development in &entry.developments {
+            let mut fields = vec![
+                development.developer_name.clone(),
+                development.dilution.clone(),
+                format!("{}°C", development.temperature.normalize()),
+            ];
+            fields.extend(PUBLISHED_PUSH_PULL.iter().map(|&push_pull| {
+                development.push_pull_times
+                    .iter()
+                    .find(|time| time.push_pull == push_pull)
+                    .map(|time| minutes_clock(time.time_minutes))
+                    .unwrap_or_else(|| "–".to_string())
+            }));
+            writer.columns(&row(&SUMMARY_COLUMNS, &fields, Face::Regular));
+            writer.rule(0.2);
+        }
+    }
+
+    if !summary.developers.is_empty() {
+        writer.heading("DEVELOPERS");
+        for developer in &summary.developers {
+            let life: Vec<String> = [
+                developer.stock_life_months.map(|months| format!("stock {} months", months)),
+                developer.working_life_hours.map(|hours| format!("working {} hours", hours)),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            writer.columns(&[
+                (0.0, developer.name.clone(), Face::Bold),
+                (0.3, format!("{}, {}", developer.manufacturer, developer.developer_type), Face::Regular),
+                (0.62, developer.dilutions.join(", "), Face::Regular),
+                (0.8, life.join(", "), Face::Regular),
+            ]);
+            writer.rule(0.2);
+        }
+    }
+
+    writer.footer(&format!("DarkroomPro · {}", timestamp));
+    writer.finish()
+}
+
+// Where the temperature columns of a chart start, after developer and dilution
+const CHART_TEMPERATURE_START: f32 = 0.47;
+// Height taken by the title band, headings and legend of a chart, in mm
+const CHART_FIXED_HEIGHT: f32 = 64.0;
+// Smallest body size a chart shrinks to before it spills onto a second page
+const CHART_MIN_BODY: f32 = 7.0;
+
+pub fn render_development_charts(charts: &[DevelopmentChart], timestamp: &str) -> Result<Vec<u8>, ExportError> {
+    let mut writer = CardWriter::new("DarkroomPro development charts", CardStyle::chart())?;
+
+    for (index, chart) in charts.iter().enumerate() {
+        if index > 0 {
+            writer.new_page();
+        }
+        writer.style.body = chart_body_size(chart, &writer.style);
+        let body = writer.style.body;
+        let film = &chart.film;
+        writer.title_band(
+            &film.name,
+            &format!("{}  ·  ISO {}  ·  {}", film.manufacturer, film.iso, film.film_type.label()),
+        );
+        if chart.rows.is_empty() {
+            writer.paragraph("No development data.", body, Face::Regular);
+            writer.footer(&format!("DarkroomPro · {}", timestamp));
+            continue;
+        }
+
+        writer.heading("PUSH / PULL  ·  minutes:seconds at the datasheet temperature");
+        let mut headers = vec!["Developer".to_string(), "Dilution".to_string(), "Temp".to_string()];
+        headers.extend(chart.push_pull_values.iter().map(|&push_pull| push_pull_label(push_pull)));
+        writer.columns(&row(&SUMMARY_COLUMNS, &headers, Face::Bold));
+        writer.rule(0.5);
+        for development in &chart.rows {
+            let fields = [
+                development.developer_name.clone(),
+                development.dilution.clone(),
+                format!("{}°C", development.temperature.normalize()),
+            ];
+            let mut cells = row(&SUMMARY_COLUMNS, &fields, Face::Regular);
+            cells.extend(SUMMARY_COLUMNS[3..].iter().zip(&development.push_pull).map(|(&position, cell)| chart_cell(position, cell)));
+            writer.columns(&cells);
+            writer.rule(0.2);
+        }
+
+        if !chart.temperatures.is_empty() {
+            let step = (1.0 - CHART_TEMPERATURE_START) / chart.temperatures.len() as f32;
+            let mut positions = vec![0.0, 0.3];
+            positions.extend((0..chart.temperatures.len()).map(|column| CHART_TEMPERATURE_START + column as f32 * step));
+
+            writer.heading("TEMPERATURE  ·  normal development");
+            let mut headers = vec!["Developer".to_string(), "Dilution".to_string()];
+            headers.extend(chart.temperatures.iter().map(|temperature| format!("{}°C", temperature.normalize())));
+            writer.columns(&row(&positions, &headers, Face::Bold));
+            writer.rule(0.5);
+            for development in &chart.rows {
+                let mut cells = row(&positions, &[development.developer_name.clone(), development.dilution.clone()], Face::Regular);
+                cells.extend(positions[2..].iter().zip(&development.temperatures).map(|(&position, cell)| chart_cell(position, cell)));
+                writer.columns(&cells);
+                writer.rule(0.2);
+            }
+        }
+
+        writer.y += body * MM_PER_POINT;
+        writer.paragraph(
+            "Bold times are from the datasheet. * Derived by DarkroomPro, not published: test before relying on it.",
+            body,
+            Face::Oblique,
+        );
+        writer.footer(&format!("DarkroomPro · {}", timestamp));
+    }
+
+    writer.finish()
+}
+
+// Largest body size, up to the style's, that keeps both tables on one page
+fn chart_body_size(chart: &DevelopmentChart, style: &CardStyle) -> f32 {
+    let tables = if chart.temperatures.is_empty() { 1 } else { 2 };
+    let rows = (tables * (chart.rows.len() + 1)) as f32;
+    let available = style.height - 2.0 * style.margin - CHART_FIXED_HEIGHT;
+    // Each row is a line of text plus its rule, see CardWriter::rule
+    let row_height = available / rows - (0.2 * MM_PER_POINT + 1.0);
+    let size = row_height / ((1.0 + LINE_SPACING) * MM_PER_POINT);
+    size.clamp(CHART_MIN_BODY, CardStyle::chart().body)
+}
+
+// Datasheet times in bold, derived ones in oblique with an asterisk so they
+// still stand out on a black and white printout
+fn chart_cell(position: f32, cell: &ChartCell) -> (f32, String, Face) {
+    match (cell.time_minutes, cell.derived) {
+        (None, _) => (position, "–".to_string(), Face::Regular),
+        (Some(time), false) => (position, minutes_clock(time), Face::Bold),
+        (Some(time), true) => (position, format!("{}*", minutes_clock(time)), Face::Oblique),
+    }
+}
+
+fn row(positions: &[f32], fields: &[String], face: Face) -> Vec<(f32, String, Face)> {
+    positions
+        .iter()
+        .zip(fields)
+        .map(|(&position, field)| (position, field.clone(), face))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Face {
+    Regular,
+    Bold,
+    Oblique,
+}
+
+// Lays text out top to bottom, starting a new page when the current one is full
+struct CardWriter {
+    document: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    oblique: IndirectFontRef,
+    style: CardStyle,
+    // Distance from the top of the page in mm
+    y: f32,
+}
+
+impl CardWriter {
+    fn new(title: &str, style: CardStyle) -> Result<Self, ExportError> {
+        let (document, page, layer) = PdfDocument::new(title, Mm(style.width), Mm(style.height), "Card");
+        let regular = document.add_builtin_font(BuiltinFont::Helvetica)?;
+        let bold = document.add_builtin_font(BuiltinFont::HelveticaBold)?;
+        let oblique = document.add_builtin_font(BuiltinFont::HelveticaOblique)?;
+        let layer = document.get_page(page).get_layer(layer);
+
+        Ok(CardWriter {
+            document,
+            layer,
+            regular,
+            bold,
+            oblique,
+            y: style.margin,
+            style,
+        })
+    }
+
+    fn content_width(&self) -> f32 {
+        self.style.width - 2.0 * self.style.margin
+    }
+
+    // White on black band across the top with the film and developer
+    fn title_band(&mut self, title: &str, subtitle: &str) {
+        let width = self.content_width();
+        let title_lines = wrap(title, self.style.title, true, width - 4.0);
+        let subtitle_lines = wrap(subtitle, self.style.heading, false, width - 4.0);
+        let height = title_lines.len() as f32 * line_height(self.style.title)
+            + subtitle_lines.len() as f32 * line_height(self.style.heading)
+            + 6.0;
+
+        self.fill_rect(self.style.margin, self.y, width, height, 0.0);
+        self.layer.set_fill_color(grey(1.0));
+        self.y += 3.0;
+        for line in title_lines {
+            self.text_line(&line, self.style.title, Face::Bold, 2.0);
+        }
+        for line in subtitle_lines {
+            self.text_line(&line, self.style.heading, Face::Regular, 2.0);
+        }
+        self.layer.set_fill_color(grey(0.0));
+        self.y += 3.0 + self.style.body * MM_PER_POINT;
+    }
+
+    fn development_time(&mut self, time: &str, conditions: &str) {
+        self.ensure_space(line_height(self.style.time));
+        self.text_line(time, self.style.time, Face::Bold, 0.0);
+        self.paragraph(conditions, self.style.heading, Face::Bold);
+    }
+
+    fn heading(&mut self, text: &str) {
+        self.y += self.style.body * MM_PER_POINT;
+        self.ensure_space(line_height(self.style.heading) + 2.0 * line_height(self.style.body));
+        self.text_line(text, self.style.heading, Face::Bold, 0.0);
+        self.rule(self.style.rule);
+        self.y += 1.0;
+    }
+
+    fn paragraph(&mut self, text: &str, size: f32, face: Face) {
+        for line in wrap(text, size, face == Face::Bold, self.content_width()) {
+            self.ensure_space(line_height(size));
+            self.text_line(&line, size, face, 0.0);
+        }
+    }
+
+    // One table row, each cell at a share of the content width and wrapped to the next cell
+    fn columns(&mut self, cells: &[(f32, String, Face)]) {
+        let width = self.content_width();
+        let size = self.style.body;
+        let wrapped: Vec<(f32, Vec<String>, Face)> = cells
+            .iter()
+            .enumerate()
+            .map(|(index, (position, text, face))| {
+                let next = cells.get(index + 1).map(|cell| cell.0).unwrap_or(1.0);
+                let cell_width = (next - position) * width - 2.0;
+                (position * width, wrap(text, size, *face == Face::Bold, cell_width), *face)
+            })
+            .collect();
+        let rows = wrapped.iter().map(|(_, lines, _)| lines.len()).max().unwrap_or(1);
+
+        self.ensure_space(rows as f32 * line_height(size));
+        let top = self.y;
+        for (offset, lines, face) in wrapped {
+            self.y = top;
+            for line in lines {
+                self.text_line(&line, size, face, offset);
+            }
+        }
+        self.y = top + rows as f32 * line_height(size);
+    }
+
+    fn rule(&mut self, thickness_pt: f32) {
+        let y = self.style.height - self.y;
+        self.layer.set_outline_thickness(thickness_pt);
+        self.layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(self.style.margin), Mm(y)), false),
+                (Point::new(Mm(self.style.width - self.style.margin), Mm(y)), false),
+            ],
+            is_closed: false,
+        });
+        self.y += thickness_pt * MM_PER_POINT + 1.0;
+    }
+
+    // Heavy border around everything written since top, or since the page break
+    fn frame(&mut self, top: f32) {
+        let top = if top > self.y { self.style.margin } else { top } - 1.0;
+        let y = self.style.height - self.y - 1.0;
+        self.layer.set_outline_thickness(self.style.rule);
+        self.layer.add_rect(
+            Rect::new(
+                Mm(self.style.margin - 1.5),
+                Mm(y),
+                Mm(self.style.width - self.style.margin + 1.5),
+                Mm(self.style.height - top),
+            )
+            .with_mode(PaintMode::Stroke),
+        );
+        self.y += 2.0;
+    }
+
+    fn footer(&mut self, text: &str) {
+        let y = self.style.margin / 2.0;
+        self.layer.use_text(text, self.style.footer, Mm(self.style.margin), Mm(y), &self.regular);
+    }
+
+    fn finish(self) -> Result<Vec<u8>, ExportError> {
+        Ok(self.document.save_to_bytes()?)
+    }
+
+    fn text_line(&mut self, text: &str, size: f32, face: Face, offset: f32) {
+        let baseline = self.y + size * MM_PER_POINT;
+        let font = match face {
+            Face::Regular => &self.regular,
+            Face::Bold => &self.bold,
+            Face::Oblique => &self.oblique,
+        };
+        self.layer.use_text(
+            text,
+            size,
+            Mm(self.style.margin + offset),
+            Mm(self.style.height - baseline),
+            font,
+        );
+        self.y += line_height(size);
+    }
+
+    fn fill_rect(&self, x: f32, top: f32, width: f32, height: f32, shade: f32) {
+        self.layer.set_fill_color(grey(shade));
+        self.layer.add_rect(
+            Rect::new(
+                Mm(x),
+                Mm(self.style.height - top - height),
+                Mm(x + width),
+                Mm(self.style.height - top),
+            )
+            .with_mode(PaintMode::Fill),
+        );
+    }
+
+    fn ensure_space(&mut self, height: f32) {
+        if self.y + height <= self.style.height - self.style.margin {
+            return;
+        }
+        self.new_page();
+    }
+
+    fn new_page(&mut self) {
+        let (page, layer) = self.document.add_page(Mm(self.style.width), Mm(self.style.height), "Card");
+        self.layer = self.document.get_page(page).get_layer(layer);
+        self.y = self.style.margin;
+    }
+}
+
+fn grey(shade: f32) -> Color {
+    Color::Greyscale(Greyscale::new(shade, None))
+}
+
+fn line_height(size: f32) -> f32 {
+    size * (1.0 + LINE_SPACING) * MM_PER_POINT
+}
+
+// Approximate Helvetica advance widths, in ems, for wrapping without font metrics
+fn text_width(text: &str, size: f32, bold: bool) -> f32 {
+    let ems: f32 = text
+        .chars()
+        .map(|c| match c {
+            ' ' | 'i' | 'j' | 'l' | '.' | ',' | ':' | ';' | '!' | '|' | '\'' => 0.28,
+            'f' | 't' | 'r' | 'I' | '(' | ')' | '-' => 0.36,
+            'm' | 'w' | 'M' | 'W' | '@' => 0.88,
+            'A'..='Z' => 0.7,
+            '0'..='9' => 0.56,
+            _ => 0.56,
+        })
+        .sum();
+    ems * size * MM_PER_POINT * if bold { 1.06 } else { 1.0 }
+}
+
+fn wrap(text: &str, size: f32, bold: bool, width: f32) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        let candidate = if current.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", current, word)
+        };
+        if text_width(&candidate, size, bold) <= width || current.is_empty() {
+            current = candidate;
+        } else {
+            lines.push(std::mem::replace(&mut current, word.to_string()));
+        }
+    }
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
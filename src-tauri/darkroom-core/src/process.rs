@@ -0,0 +1,239 @@
+use crate::calculator::{CalculationEngine, CalculationError};
+use crate::models::*;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+const POUR_SECONDS: u32 = 10;
+const DRAIN_SECONDS: u32 = 10;
+
+// Black and white steps after the developer, from the database's typical times
+const STOP_BATH_SECONDS: u32 = 30;
+const FIXER_SECONDS: u32 = 300;
+const BW_WASH_SECONDS: u32 = 1200;
+
+// Color steps used when the film's developer data leaves them out
+const COLOR_WASH_SECONDS: u32 = 180;
+const C41_BLEACH_SECONDS: u32 = 390;
+const C41_FIXER_SECONDS: u32 = 390;
+const E6_REVERSAL_SECONDS: u32 = 120;
+const E6_COLOR_DEV_SECONDS: u32 = 360;
+const E6_BLEACH_SECONDS: u32 = 360;
+const E6_FIXER_SECONDS: u32 = 240;
+const STABILIZER_SECONDS: u32 = 60;
+
+impl CalculationEngine {
+    pub fn build_process_plan(&self, request: &ProcessPlanRequest) -> Result<ProcessPlan, CalculationError> {
+        let calculation = self.calculate_development(request.calculation.clone())?;
+        let film = self.get_film_info(&request.calculation.film_key)?;
+        let dev_data = self.find_developer_data(film, &request.calculation.developer_key)?;
+
+        let temperature = Some(request.calculation.temperature);
+        let developer_seconds = minutes_to_seconds(calculation.time_minutes);
+        let developer_agitation = AgitationSchedule {
+            initial_seconds: dev_data.agitation_initial_seconds,
+            duration_seconds: dev_data.agitation_interval_seconds,
+            every_seconds: minutes_to_seconds(dev_data.agitation_frequency_minutes),
+        };
+
+        let mut steps = Vec::new();
+        if request.pre_soak_seconds > 0 {
+            steps.push(step(ProcessStepKind::PreSoak, "Pre-soak", request.pre_soak_seconds, temperature, None));
+        }
+
+        match film.film_type {
+            FilmType::BlackWhite => {
+                steps.push(step(ProcessStepKind::Developer, "Developer", developer_seconds, temperature, Some(developer_agitation)));
+                steps.push(step(ProcessStepKind::StopBath, "Stop bath", STOP_BATH_SECONDS, temperature, Some(continuous_agitation(STOP_BATH_SECONDS))));
+                steps.push(step(ProcessStepKind::Fixer, "Fixer", FIXER_SECONDS, temperature, Some(standard_agitation())));
+                steps.push(step(ProcessStepKind::Wash, "Wash", BW_WASH_SECONDS, temperature, None));
+            },
+            FilmType::ColorNegative => {
+                let step_temperature = |value: Option<Decimal>| value.or(temperature);
+                steps.push(step(ProcessStepKind::Developer, "Developer", developer_seconds, temperature, Some(developer_agitation.clone())));
+                steps.push(step(
+                    ProcessStepKind::Bleach,
+                    "Bleach",
+                    dev_data.bleach_time_minutes.map(minutes_to_seconds).unwrap_or(C41_BLEACH_SECONDS),
+                    step_temperature(dev_data.bleach_temp_c),
+                    Some(developer_agitation.clone()),
+                ));
+                steps.push(step(ProcessStepKind::Wash, "Wash", COLOR_WASH_SECONDS, temperature, None));
+                steps.push(step(
+                    ProcessStepKind::Fixer,
+                    "Fixer",
+                    dev_data.fixer_time_minutes.map(minutes_to_seconds).unwrap_or(C41_FIXER_SECONDS),
+                    step_temperature(dev_data.fixer_temp_c),
+                    Some(developer_agitation),
+                ));
+                steps.push(step(ProcessStepKind::Wash, "Wash", COLOR_WASH_SECONDS, temperature, None));
+                steps.push(step(
+                    ProcessStepKind::Stabilizer,
+                    "Stabilizer",
+                    dev_data.stabilizer_time_minutes.map(minutes_to_seconds).unwrap_or(STABILIZER_SECONDS),
+                    step_temperature(dev_data.stabilizer_temp_c),
+                    None,
+                ));
+            },
+            FilmType::Slide => {
+                let step_temperature = |value: Option<Decimal>| value.or(temperature);
+                steps.push(step(ProcessStepKind::FirstDeveloper, "First developer", developer_seconds, temperature, Some(developer_agitation.clone())));
+                steps.push(step(ProcessStepKind::Wash, "Wash", COLOR_WASH_SECONDS, temperature, None));
+                steps.push(step(
+                    ProcessStepKind::Reversal,
+                    "Reversal",
+                    dev_data.reversal_time_minutes.map(minutes_to_seconds).unwrap_or(E6_REVERSAL_SECONDS),
+                    step_temperature(dev_data.reversal_temp_c),
+                    Some(standard_agitation()),
+                ));
+                steps.push(step(
+                    ProcessStepKind::ColorDeveloper,
+                    "Color developer",
+                    dev_data.color_dev_time_minutes.map(minutes_to_seconds).unwrap_or(E6_COLOR_DEV_SECONDS),
+                    step_temperature(dev_data.color_dev_temp_c),
+                    Some(developer_agitation.clone()),
+                ));
+                steps.push(step(
+                    ProcessStepKind::Bleach,
+                    "Bleach",
+                    dev_data.bleach_time_minutes.map(minutes_to_seconds).unwrap_or(E6_BLEACH_SECONDS),
+                    step_temperature(dev_data.bleach_temp_c),
+                    Some(developer_agitation.clone()),
+                ));
+                steps.push(step(
+                    ProcessStepKind::Fixer,
+                    "Fixer",
+                    dev_data.fixer_time_minutes.map(minutes_to_seconds).unwrap_or(E6_FIXER_SECONDS),
+                    step_temperature(dev_data.fixer_temp_c),
+                    Some(developer_agitation),
+                ));
+                steps.push(step(ProcessStepKind::Wash, "Wash", COLOR_WASH_SECONDS, temperature, None));
+                steps.push(step(
+                    ProcessStepKind::Stabilizer,
+                    "Stabilizer",
+                    dev_data.stabilizer_time_minutes.map(minutes_to_seconds).unwrap_or(STABILIZER_SECONDS),
+                    step_temperature(dev_data.stabilizer_temp_c),
+                    None,
+                ));
+            },
+        }
+
+        Ok(ProcessPlan {
+            name: request.label.clone().unwrap_or_else(|| {
+                format!("{} in {} {}", calculation.film_name, calculation.developer_name, calculation.dilution)
+            }),
+            steps,
+        })
+    }
+}
+
+impl ProcessPlan {
+    pub fn total_seconds(&self) -> u32 {
+        self.steps.iter().map(|step| step.duration_seconds).sum()
+    }
+
+    /// Offset of every step from the start of the plan
+    pub fn step_starts(&self) -> Vec<u32> {
+        self.steps
+            .iter()
+            .scan(0, |offset, step| {
+                let start = *offset;
+                *offset += step.duration_seconds;
+                Some(start)
+            })
+            .collect()
+    }
+
+    /// Every hands-on action in the plan, in time order
+    pub fn actions(&self) -> Vec<ProcessAction> {
+        let mut actions = Vec::new();
+
+        for (step_index, (step, start)) in self.steps.iter().zip(self.step_starts()).enumerate() {
+            // A phase too short for its full drain still drains, in whatever is left after the pour
+            let drain_start = step.duration_seconds
+                .saturating_sub(step.drain_seconds)
+                .max(step.pour_seconds.min(step.duration_seconds));
+            let mut push = |kind, offset: u32, duration: u32| {
+                if duration > 0 {
+                    actions.push(ProcessAction {
+                        kind,
+                        step_index,
+                        start_seconds: start + offset,
+                        duration_seconds: duration,
+                    });
+                }
+            };
+
+            push(ProcessActionKind::Pour, 0, step.pour_seconds.min(step.duration_seconds));
+
+            if let Some(agitation) = &step.agitation {
+                let initial_end = (step.pour_seconds + agitation.initial_seconds).min(drain_start);
+                push(ProcessActionKind::Agitate, step.pour_seconds.min(initial_end), initial_end.saturating_sub(step.pour_seconds));
+
+                if agitation.every_seconds > 0 && agitation.duration_seconds > 0 {
+                    let mut offset = agitation.every_seconds;
+                    while offset + agitation.duration_seconds <= drain_start {
+                        if offset >= initial_end {
+                            push(ProcessActionKind::Agitate, offset, agitation.duration_seconds);
+                        }
+                        offset += agitation.every_seconds;
+                    }
+                }
+            }
+
+            if step.drain_seconds > 0 {
+                push(ProcessActionKind::Drain, drain_start, step.duration_seconds - drain_start);
+            }
+        }
+
+        actions
+    }
+}
+
+impl AgitationSchedule {
+    /// Short description for printed recipes, e.g. "30s, then 10s every 60s"
+    pub fn describe(&self) -> String {
+        if self.every_seconds == 0 || self.duration_seconds == 0 {
+            format!("Continuous for {}s", self.initial_seconds)
+        } else {
+            format!("{}s, then {}s every {}s", self.initial_seconds, self.duration_seconds, self.every_seconds)
+        }
+    }
+}
+
+pub fn minutes_to_seconds(minutes: Decimal) -> u32 {
+    (minutes * Decimal::from(60)).round().to_u32().unwrap_or(0)
+}
+
+fn step(
+    kind: ProcessStepKind,
+    name: &str,
+    duration_seconds: u32,
+    temperature: Option<Decimal>,
+    agitation: Option<AgitationSchedule>,
+) -> ProcessStep {
+    ProcessStep {
+        kind,
+        name: name.to_string(),
+        duration_seconds,
+        temperature,
+        agitation,
+        pour_seconds: POUR_SECONDS,
+        drain_seconds: DRAIN_SECONDS,
+    }
+}
+
+fn standard_agitation() -> AgitationSchedule {
+    AgitationSchedule {
+        initial_seconds: 30,
+        duration_seconds: 10,
+        every_seconds: 60,
+    }
+}
+
+fn continuous_agitation(duration_seconds: u32) -> AgitationSchedule {
+    AgitationSchedule {
+        initial_seconds: duration_seconds,
+        duration_seconds: 0,
+        every_seconds: 0,
+    }
+}
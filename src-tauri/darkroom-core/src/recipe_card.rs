@@ -0,0 +1,97 @@
+use crate::calculator::{CalculationEngine, CalculationError};
+use crate::catalogue::push_pull_label;
+use crate::models::*;
+use rust_decimal::Decimal;
+
+// Chemistry above this is warm enough to mention handling it with care
+const WARM_CHEMISTRY_C: u32 = 30;
+
+impl CalculationEngine {
+    pub fn build_recipe_card(&self, request: &ProcessPlanRequest) -> Result<RecipeCard, CalculationError> {
+        let database = self.get_database()?;
+        let mut calculation = self.calculate_development(request.calculation.clone())?;
+        let plan = self.build_process_plan(request)?;
+        let developer = self.find_developer(&database.developers, &request.calculation.developer_key)?;
+
+        let mut safety_warnings = Vec::new();
+        if let Some(notes) = &developer.safety_notes {
+            // Listed with the other warnings, so not again among the calculation notes
+            let safety_note = format!("Safety: {}", notes);
+            calculation.notes.retain(|note| *note != safety_note);
+            safety_warnings.push(format!("{}: {}", developer.name, notes));
+        }
+        if calculation.film_type != FilmType::BlackWhite {
+            safety_warnings.push("Bleach and fixer irritate skin and eyes, wear nitrile gloves and eye protection".to_string());
+        }
+        if calculation.temperature >= Decimal::from(WARM_CHEMISTRY_C) {
+            safety_warnings.push(format!("Chemistry is at {}°C, handle warm solutions with care", calculation.temperature.normalize()));
+        }
+        if let Some(note) = &database.metadata.notes.safety_note {
+            safety_warnings.push(note.clone());
+        }
+
+        Ok(RecipeCard {
+            calculation,
+            plan: Some(plan),
+            safety_warnings,
+        })
+    }
+}
+
+impl RecipeCard {
+    /// Temperature and push/pull, e.g. ["20°C", "Push 1"]
+    pub fn conditions(&self) -> Vec<String> {
+        let calc = &self.calculation;
+        let mut conditions = vec![format!("{}°C", calc.temperature.normalize())];
+        if calc.push_pull != 0 {
+            conditions.push(push_pull_label(calc.push_pull));
+        }
+        conditions
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::test_support::engine;
+
+    fn request(developer_key: &str) -> ProcessPlanRequest {
+        ProcessPlanRequest {
+            calculation: CalculationRequest {
+                film_key: "ilford_hp5_plus".to_string(),
+                developer_key: developer_key.to_string(),
+                temperature: Decimal::from(20),
+                push_pull: 0,
+                volume: 500,
+            },
+            label: None,
+            pre_soak_seconds: 0,
+        }
+    }
+
+    #[test]
+    fn safety_notes_appear_once_on_the_card() {
+        let engine = engine();
+        let request = request("kodak_d76_stock");
+        let calculation = engine.calculate_development(request.calculation.clone()).unwrap();
+        assert!(calculation.notes.iter().any(|note| note.starts_with("Safety: ")));
+
+        let card = engine.build_recipe_card(&request).unwrap();
+        assert!(card.calculation.notes.iter().all(|note| !note.starts_with("Safety: ")));
+        assert_eq!(card.safety_warnings.iter().filter(|warning| warning.starts_with("Kodak D-76: ")).count(), 1);
+        assert_eq!(card.calculation.notes.len(), calculation.notes.len() - 1);
+    }
+
+    #[test]
+    fn conditions_name_the_direction_once() {
+        let engine = engine();
+        let mut request = request("kodak_d76_stock");
+        let card = engine.build_recipe_card(&request).unwrap();
+        assert_eq!(card.conditions(), vec!["20°C"]);
+
+        request.calculation.push_pull = -1;
+        assert_eq!(engine.build_recipe_card(&request).unwrap().conditions(), vec!["20°C", "Pull 1"]);
+        request.calculation.push_pull = 2;
+        assert_eq!(engine.build_recipe_card(&request).unwrap().conditions(), vec!["20°C", "Push 2"]);
+    }
+}
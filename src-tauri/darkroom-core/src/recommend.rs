@@ -0,0 +1,293 @@
+use crate::calculator::{CalculationEngine, CalculationError};
+use crate::models::*;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+// Working solution needed for one 35mm roll when a developer is used one-shot
+const ONE_SHOT_VOLUME_ML: u32 = 300;
+// Score used for every goal when the request does not name any
+const NEUTRAL_SCORE: i32 = 50;
+
+struct Candidate<'a> {
+    key: &'a String,
+    data: &'a DeveloperData,
+    developer: Option<&'a Developer>,
+    dilution: DilutionRatio,
+    time_minutes: Option<Decimal>,
+    // Published time for the requested push, or +1 when none is requested
+    push_time_minutes: Option<Decimal>,
+    cost_per_roll: Option<Decimal>,
+    in_inventory: bool,
+}
+
+// Spread of a value across all candidates, used to score relative goals
+struct Range {
+    min: f64,
+    max: f64,
+}
+
+impl CalculationEngine {
+    pub fn recommend_developers(&self, request: &RecommendationRequest) -> Result<Vec<DeveloperRecommendation>, CalculationError> {
+        let database = self.get_database()?;
+
+        if request.push_pull < -2 || request.push_pull > 3 {
+            return Err(CalculationError::InvalidPushPull(request.push_pull));
+        }
+
+        let film = self.get_film_info(&request.film_key)?;
+
+        // Inventory entries may name a developer or one of its dilutions
+        let inventory: Vec<&Developer> = request.inventory
+            .iter()
+            .filter_map(|key| self.find_developer(&database.developers, key).ok())
+            .collect();
+
+        let candidates: Vec<Candidate> = film.developers
+            .iter()
+            .map(|(key, data)| {
+                let developer = self.find_developer(&database.developers, key).ok();
+                let dilution = self.parse_dilution(&data.dilution)
+                    .unwrap_or(DilutionRatio { developer: 1, water: 0 });
+                let cost_per_roll = developer.and_then(|developer| cost_per_roll(developer, &dilution));
+                let in_inventory = request.inventory.contains(key)
+                    || developer.is_some_and(|developer| inventory.iter().any(|owned| std::ptr::eq(*owned, developer)));
+
+                Candidate {
+                    key,
+                    data,
+                    developer,
+                    dilution,
+                    time_minutes: self.get_datasheet_time(film, data, request.push_pull),
+                    push_time_minutes: self.get_datasheet_time(film, data, request.push_pull.max(1)),
+                    cost_per_roll,
+                    in_inventory,
+                }
+            })
+            .collect();
+
+        let cost_range = Range::of(candidates.iter().filter_map(|candidate| candidate.cost_per_roll.and_then(|cost| cost.to_f64())));
+        let shelf_life_range = Range::of(candidates
+            .iter()
+            .filter_map(|candidate| candidate.developer.and_then(|developer| developer.stock_life_months))
+            .map(f64::from));
+
+        let mut recommendations: Vec<DeveloperRecommendation> = candidates
+            .iter()
+            .map(|candidate| {
+                let mut reasons = Vec::new();
+
+                let goal_points: Vec<i32> = request.goals
+                    .iter()
+                    .map(|&goal| {
+                        let (points, explanation) = score_goal(goal, candidate, request.push_pull, &cost_range, &shelf_life_range);
+                        reasons.push(RecommendationReason { goal: Some(goal), points, explanation });
+                        points
+                    })
+                    .collect();
+
+                let mut score = if goal_points.is_empty() {
+                    NEUTRAL_SCORE
+                } else {
+                    goal_points.iter().sum::<i32>() / goal_points.len() as i32
+                };
+
+                for reason in film_adjustments(film, candidate, request.push_pull) {
+                    score += reason.points;
+                    reasons.push(reason);
+                }
+
+                DeveloperRecommendation {
+                    developer_key: candidate.key.clone(),
+                    developer_name: candidate.developer
+                        .map(|developer| developer.name.clone())
+                        .unwrap_or_else(|| candidate.key.clone()),
+                    dilution: candidate.data.display_dilution(),
+                    score: score.clamp(0, 100) as u32,
+                    time_minutes: candidate.time_minutes,
+                    cost_per_roll_usd: candidate.cost_per_roll,
+                    in_inventory: candidate.in_inventory,
+                    reasons,
+                }
+            })
+            .collect();
+
+        recommendations.sort_by(|a, b| {
+            b.score.cmp(&a.score)
+                .then_with(|| b.in_inventory.cmp(&a.in_inventory))
+                .then_with(|| a.developer_name.cmp(&b.developer_name))
+                .then_with(|| a.developer_key.cmp(&b.developer_key))
+        });
+        if let Some(limit) = request.limit {
+            recommendations.truncate(limit);
+        }
+
+        Ok(recommendations)
+    }
+}
+
+impl Range {
+    fn of(values: impl Iterator<Item = f64>) -> Self {
+        values.fold(Range { min: f64::MAX, max: f64::MIN }, |range, value| Range {
+            min: range.min.min(value),
+            max: range.max.max(value),
+        })
+    }
+
+    // 0 at the low end of the range, 100 at the high end
+    fn position(&self, value: f64) -> i32 {
+        if self.max <= self.min {
+            return 100;
+        }
+        ((value - self.min) / (self.max - self.min) * 100.0).round() as i32
+    }
+}
+
+fn score_goal(
+    goal: DevelopmentGoal,
+    candidate: &Candidate,
+    push_pull: i32,
+    cost_range: &Range,
+    shelf_life_range: &Range,
+) -> (i32, String) {
+    let Some(developer) = candidate.developer else {
+        return (0, "No developer details in the database".to_string());
+    };
+
+    match goal {
+        DevelopmentGoal::FinestGrain => {
+            let (mut points, mut explanation) = if has_trait(developer, &["ultra_fine_grain"], &["ultra-fine grain"]) {
+                (100, "Ultra-fine grain developer".to_string())
+            } else if has_trait(developer, &["fine_grain"], &["fine grain"]) {
+                (75, "Fine grain developer".to_string())
+            } else if has_trait(developer, &["high_contrast", "sharpness"], &["acutance"]) {
+                (30, "Favors sharpness over fine grain".to_string())
+            } else {
+                (50, "Average grain".to_string())
+            };
+            if candidate.dilution.water >= 25 {
+                points -= 20;
+                explanation.push_str(&format!("; high dilution ({}) emphasizes grain", candidate.data.dilution));
+            }
+            (points.clamp(0, 100), explanation)
+        },
+        DevelopmentGoal::MaximumAcutance => {
+            let (mut points, mut explanation) = if has_trait(developer, &["maximum_sharpness", "sharpness"], &["legendary sharpness"]) {
+                (100, "Known for maximum sharpness".to_string())
+            } else if has_trait(developer, &["sharp_results"], &["acutance", "sharp"]) {
+                (75, "Sharp results".to_string())
+            } else {
+                (40, "Not aimed at acutance".to_string())
+            };
+            if candidate.dilution.water > 0 {
+                points += 10;
+                explanation.push_str(&format!("; diluted {} for stronger edge effects", candidate.data.dilution));
+            }
+            (points.clamp(0, 100), explanation)
+        },
+        DevelopmentGoal::PushProcessing => {
+            let stops = push_pull.max(1);
+            let (mut points, mut explanation) = if candidate.push_time_minutes.is_some() {
+                (60, format!("Published time for +{}", stops))
+            } else {
+                (20, format!("No published time for +{}, it would be extrapolated", stops))
+            };
+            if has_trait(developer, &["push_processing", "speed_enhancement"], &["film speed"]) {
+                points += 40;
+                explanation.push_str("; suited to push processing");
+            }
+            (points.clamp(0, 100), explanation)
+        },
+        DevelopmentGoal::LowestCost => match candidate.cost_per_roll {
+            Some(cost) => (
+                100 - cost_range.position(cost.to_f64().unwrap_or(0.0)),
+                format!("About ${} per roll", cost),
+            ),
+            None => (0, "No price data".to_string()),
+        },
+        DevelopmentGoal::LongestShelfLife => match developer.stock_life_months {
+            Some(months) => (
+                shelf_life_range.position(f64::from(months)),
+                format!("Stock keeps {} months", months),
+            ),
+            None => (0, "No shelf life data".to_string()),
+        },
+        DevelopmentGoal::InInventory => if candidate.in_inventory {
+            (100, "Already in your inventory".to_string())
+        } else {
+            (0, "Not in your inventory".to_string())
+        },
+    }
+}
+
+// Adjustments that apply whatever the goals, from how well the developer suits this film
+fn film_adjustments(film: &Film, candidate: &Candidate, push_pull: i32) -> Vec<RecommendationReason> {
+    let mut reasons = Vec::new();
+    let Some(developer) = candidate.developer else {
+        return reasons;
+    };
+
+    let film_name = film.name.to_lowercase().replace('-', "");
+    for use_case in &developer.best_for {
+        let suited = match use_case.as_str() {
+            "tmax_films" => film_name.contains("tmax"),
+            "delta_films" => film_name.contains("delta"),
+            "ilford_films" => film.manufacturer.eq_ignore_ascii_case("ilford"),
+            "slow_films" => film.iso <= 100,
+            _ => false,
+        };
+        if suited {
+            reasons.push(RecommendationReason {
+                goal: None,
+                points: 10,
+                explanation: format!("{} recommends it for {}", developer.manufacturer, use_case.replace('_', " ")),
+            });
+        }
+    }
+
+    for avoid in &developer.avoid_with {
+        let clash = match avoid.as_str() {
+            "high_speed_films" => film.iso >= 400,
+            "slow_films" => film.iso <= 100,
+            _ => false,
+        };
+        if clash {
+            reasons.push(RecommendationReason {
+                goal: None,
+                points: -25,
+                explanation: format!("Not recommended for {}", avoid.replace('_', " ")),
+            });
+        }
+    }
+
+    if push_pull != 0 && candidate.time_minutes.is_none() {
+        reasons.push(RecommendationReason {
+            goal: None,
+            points: -15,
+            explanation: format!("No published time for {:+} stops", push_pull),
+        });
+    }
+
+    reasons
+}
+
+fn has_trait(developer: &Developer, best_for: &[&str], characteristics: &[&str]) -> bool {
+    let description = developer.characteristics.to_lowercase();
+    developer.best_for.iter().any(|use_case| best_for.contains(&use_case.as_str()))
+        || characteristics.iter().any(|phrase| description.contains(phrase))
+}
+
+// Stock solutions are reused up to their capacity, diluted ones are used one-shot
+fn cost_per_roll(developer: &Developer, dilution: &DilutionRatio) -> Option<Decimal> {
+    let price = developer.price_per_liter_usd?;
+    let total_parts = dilution.developer + dilution.water;
+
+    let cost = if dilution.water == 0 || total_parts == 0 {
+        let capacity = developer.capacity_rolls_per_liter.filter(|&capacity| capacity > 0)?;
+        price / Decimal::from(capacity)
+    } else {
+        let concentrate_ml = Decimal::from(ONE_SHOT_VOLUME_ML * dilution.developer) / Decimal::from(total_parts);
+        price * concentrate_ml / Decimal::from(1000)
+    };
+
+    Some(cost.round_dp(2))
+}
@@ -0,0 +1,265 @@
+use crate::models::*;
+
+const DEFAULT_MIN_POUR_GAP_SECONDS: u32 = 30;
+// Time to move between tanks for any other hands-on action
+const HANDOVER_SECONDS: u32 = 2;
+// Every tank order is tried up to this many plans, larger sessions use a few heuristic orders
+const MAX_EXHAUSTIVE_PLANS: usize = 5;
+
+pub struct SessionScheduler {
+    min_pour_gap_seconds: u32,
+}
+
+// A hands-on action placed on the session timeline
+#[derive(Clone, Copy)]
+struct Slot {
+    start: u32,
+    end: u32,
+    handles_liquid: bool,
+}
+
+struct Placement {
+    offsets: Vec<u32>,
+    total_seconds: u32,
+}
+
+impl SessionScheduler {
+    pub fn new(min_pour_gap_seconds: Option<u32>) -> Self {
+        Self {
+            min_pour_gap_seconds: min_pour_gap_seconds.unwrap_or(DEFAULT_MIN_POUR_GAP_SECONDS),
+        }
+    }
+
+    /// Staggers the tanks so one person can handle every pour, drain and agitation,
+    /// picking the tank order that finishes the whole session earliest
+    pub fn schedule(&self, plans: &[ProcessPlan]) -> SessionSchedule {
+        let actions: Vec<Vec<ProcessAction>> = plans.iter().map(|plan| plan.actions()).collect();
+        let durations: Vec<u32> = plans.iter().map(|plan| plan.total_seconds()).collect();
+
+        let best = candidate_orders(&durations)
+            .into_iter()
+            .map(|order| self.place(&order, &actions, &durations))
+            .min_by(|a, b| {
+                a.total_seconds.cmp(&b.total_seconds)
+                    .then_with(|| a.offsets.iter().sum::<u32>().cmp(&b.offsets.iter().sum::<u32>()))
+            })
+            .unwrap_or(Placement { offsets: Vec::new(), total_seconds: 0 });
+
+        let tanks = plans
+            .iter()
+            .enumerate()
+            .map(|(plan_index, plan)| {
+                let offset = best.offsets[plan_index];
+                ScheduledTank {
+                    plan_index,
+                    name: plan.name.clone(),
+                    start_seconds: offset,
+                    end_seconds: offset + durations[plan_index],
+                    steps: plan.steps
+                        .iter()
+                        .zip(plan.step_starts())
+                        .enumerate()
+                        .map(|(step_index, (step, start))| ScheduledStep {
+                            step_index,
+                            kind: step.kind,
+                            name: step.name.clone(),
+                            start_seconds: offset + start,
+                            end_seconds: offset + start + step.duration_seconds,
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        let mut scheduled_actions: Vec<ScheduledAction> = actions
+            .into_iter()
+            .enumerate()
+            .flat_map(|(plan_index, plan_actions)| {
+                let offset = best.offsets[plan_index];
+                let plan = &plans[plan_index];
+                plan_actions.into_iter().map(move |mut action| {
+                    action.start_seconds += offset;
+                    ScheduledAction {
+                        plan_index,
+                        tank_name: plan.name.clone(),
+                        step_name: plan.steps[action.step_index].name.clone(),
+                        action,
+                    }
+                })
+            })
+            .collect();
+        scheduled_actions.sort_by_key(|scheduled| (scheduled.action.start_seconds, scheduled.plan_index));
+
+        SessionSchedule {
+            total_seconds: best.total_seconds,
+            min_pour_gap_seconds: self.min_pour_gap_seconds,
+            tanks,
+            actions: scheduled_actions,
+        }
+    }
+
+    // Starts each tank, in the given order, at the earliest offset that clashes with no earlier tank
+    fn place(&self, order: &[usize], actions: &[Vec<ProcessAction>], durations: &[u32]) -> Placement {
+        let mut offsets = vec![0; actions.len()];
+        let mut placed: Vec<Slot> = Vec::new();
+        let mut total_seconds = 0;
+
+        for &plan_index in order {
+            let slots: Vec<Slot> = actions[plan_index].iter().map(Slot::from).collect();
+
+            let mut offset = 0;
+            while let Some(shift) = self.first_conflict(&slots, &placed, offset) {
+                offset = shift;
+            }
+
+            placed.extend(slots.iter().map(|slot| Slot {
+                start: slot.start + offset,
+                end: slot.end + offset,
+                handles_liquid: slot.handles_liquid,
+            }));
+            offsets[plan_index] = offset;
+            total_seconds = total_seconds.max(offset + durations[plan_index]);
+        }
+
+        Placement { offsets, total_seconds }
+    }
+
+    // The smallest later offset that clears the first clash found, if any
+    fn first_conflict(&self, slots: &[Slot], placed: &[Slot], offset: u32) -> Option<u32> {
+        for slot in slots {
+            let start = slot.start + offset;
+            let end = slot.end + offset;
+            for other in placed {
+                let gap = if slot.handles_liquid && other.handles_liquid {
+                    self.min_pour_gap_seconds
+                } else {
+                    HANDOVER_SECONDS
+                };
+                if start < other.end + gap && other.start < end + gap {
+                    return Some(other.end + gap - slot.start);
+                }
+            }
+        }
+        None
+    }
+}
+
+impl From<&ProcessAction> for Slot {
+    fn from(action: &ProcessAction) -> Self {
+        Slot {
+            start: action.start_seconds,
+            end: action.start_seconds + action.duration_seconds,
+            handles_liquid: action.kind != ProcessActionKind::Agitate,
+        }
+    }
+}
+
+fn candidate_orders(durations: &[u32]) -> Vec<Vec<usize>> {
+    let given: Vec<usize> = (0..durations.len()).collect();
+    if durations.len() <= MAX_EXHAUSTIVE_PLANS {
+        return permutations(&given);
+    }
+
+    let mut longest_first = given.clone();
+    longest_first.sort_by_key(|&index| std::cmp::Reverse(durations[index]));
+    let mut shortest_first = given.clone();
+    shortest_first.sort_by_key(|&index| durations[index]);
+
+    vec![given, longest_first, shortest_first]
+}
+
+fn permutations(items: &[usize]) -> Vec<Vec<usize>> {
+    if items.len() <= 1 {
+        return vec![items.to_vec()];
+    }
+
+    let mut result = Vec::new();
+    for (index, &first) in items.iter().enumerate() {
+        let mut rest = items.to_vec();
+        rest.remove(index);
+        for mut tail in permutations(&rest) {
+            tail.insert(0, first);
+            result.push(tail);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // One developer step: a pour from 0 to 10 and a drain from 50 to 60
+    fn plan(name: &str, agitation: Option<AgitationSchedule>) -> ProcessPlan {
+        ProcessPlan {
+            name: name.to_string(),
+            steps: vec![ProcessStep {
+                kind: ProcessStepKind::Developer,
+                name: "Developer".to_string(),
+                duration_seconds: 60,
+                temperature: None,
+                agitation,
+                pour_seconds: 10,
+                drain_seconds: 10,
+            }],
+        }
+    }
+
+    fn offsets(schedule: &SessionSchedule) -> Vec<u32> {
+        schedule.tanks.iter().map(|tank| tank.start_seconds).collect()
+    }
+
+    #[test]
+    fn pours_and_drains_keep_the_minimum_gap() {
+        // Anything earlier puts the second pour within 30 seconds of the first pour or drain
+        let schedule = SessionScheduler::new(None).schedule(&[plan("A", None), plan("B", None)]);
+        assert_eq!(offsets(&schedule), vec![0, 90]);
+        assert_eq!((schedule.total_seconds, schedule.min_pour_gap_seconds), (150, 30));
+
+        let schedule = SessionScheduler::new(Some(5)).schedule(&[plan("A", None), plan("B", None)]);
+        assert_eq!(offsets(&schedule), vec![0, 15]);
+        assert_eq!(schedule.total_seconds, 75);
+    }
+
+    #[test]
+    fn agitations_only_need_a_handover() {
+        let agitation = AgitationSchedule { initial_seconds: 0, duration_seconds: 5, every_seconds: 20 };
+        let plans = [plan("A", None), plan("B", None), plan("C", Some(agitation))];
+        let schedule = SessionScheduler::new(Some(20)).schedule(&plans);
+
+        let slots: Vec<(usize, Slot)> = schedule.actions.iter().map(|scheduled| (scheduled.plan_index, Slot::from(&scheduled.action))).collect();
+        for (index, (tank, slot)) in slots.iter().enumerate() {
+            for (other_tank, other) in &slots[index + 1..] {
+                if tank == other_tank {
+                    continue;
+                }
+                let gap = if slot.handles_liquid && other.handles_liquid { 20 } else { HANDOVER_SECONDS };
+                assert!(
+                    slot.end + gap <= other.start || other.end + gap <= slot.start,
+                    "{}-{} in tank {} and {}-{} in tank {} are too close",
+                    slot.start, slot.end, tank, other.start, other.end, other_tank
+                );
+            }
+        }
+        assert!(schedule.actions.windows(2).all(|pair| pair[0].action.start_seconds <= pair[1].action.start_seconds));
+    }
+
+    #[test]
+    fn longer_tanks_go_first_when_that_finishes_sooner() {
+        let mut long = plan("Long", None);
+        long.steps[0].duration_seconds = 300;
+        let schedule = SessionScheduler::new(None).schedule(&[plan("Short", None), long]);
+
+        // Starting the short tank first would end the session at 90 + 300
+        assert_eq!(offsets(&schedule), vec![40, 0]);
+        assert_eq!(schedule.total_seconds, 300);
+        assert_eq!(schedule.tanks[0].steps[0].end_seconds, 100);
+    }
+
+    #[test]
+    fn no_plans_make_an_empty_session() {
+        let schedule = SessionScheduler::new(None).schedule(&[]);
+        assert_eq!(schedule.total_seconds, 0);
+        assert!(schedule.tanks.is_empty() && schedule.actions.is_empty());
+    }
+}
@@ -0,0 +1,522 @@
+use crate::models::*;
+use std::collections::HashMap;
+
+// Score for each way a query can hit a field, before the field bonus
+const SCORE_EXACT: u32 = 100;
+const SCORE_PREFIX: u32 = 90;
+const SCORE_WORD_PREFIX: u32 = 80;
+const SCORE_SUBSTRING: u32 = 60;
+const SCORE_FUZZY: u32 = 50;
+const FUZZY_PENALTY_PER_TYPO: u32 = 10;
+
+#[derive(Default)]
+pub struct SearchIndex {
+    entries: Vec<IndexEntry>,
+}
+
+struct IndexEntry {
+    kind: CatalogueKind,
+    key: String,
+    name: String,
+    manufacturer: String,
+    fields: Vec<IndexedField>,
+}
+
+struct IndexedField {
+    field: SearchField,
+    value: String,
+    // Lowercased alphanumeric characters of the value, punctuation and spaces removed
+    chars: Vec<char>,
+    // Character offset in `value` of every entry in `chars`
+    offsets: Vec<usize>,
+    // Indices into `chars` where a word starts
+    word_starts: Vec<usize>,
+    mask: u64,
+}
+
+struct Needle {
+    chars: Vec<char>,
+    mask: u64,
+}
+
+#[derive(Clone, Copy)]
+struct FieldHit {
+    field_index: usize,
+    start: usize,
+    end: usize,
+    typos: u32,
+    score: u32,
+}
+
+impl SearchIndex {
+    pub fn build(database: &Database) -> Self {
+        let mut entries = Vec::with_capacity(database.films.len() + database.developers.len());
+
+        for (key, film) in &database.films {
+            let mut fields = vec![
+                IndexedField::new(SearchField::Name, &film.name),
+                IndexedField::new(SearchField::Manufacturer, &film.manufacturer),
+                IndexedField::new(SearchField::Key, key),
+                IndexedField::new(SearchField::Iso, &film.iso.to_string()),
+            ];
+            fields.extend(film.alternative_names.iter().map(|alias| IndexedField::new(SearchField::AlternativeName, alias)));
+
+            entries.push(IndexEntry {
+                kind: CatalogueKind::Film,
+                key: key.clone(),
+                name: film.name.clone(),
+                manufacturer: film.manufacturer.clone(),
+                fields,
+            });
+        }
+
+        for (key, developer) in &database.developers {
+            let mut fields = vec![
+                IndexedField::new(SearchField::Name, &developer.name),
+                IndexedField::new(SearchField::Manufacturer, &developer.manufacturer),
+                IndexedField::new(SearchField::Key, key),
+            ];
+            fields.extend(developer.alternative_names.iter().map(|alias| IndexedField::new(SearchField::AlternativeName, alias)));
+
+            entries.push(IndexEntry {
+                kind: CatalogueKind::Developer,
+                key: key.clone(),
+                name: developer.name.clone(),
+                manufacturer: developer.manufacturer.clone(),
+                fields,
+            });
+        }
+
+        Self { entries }
+    }
+
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchResult> {
+        // The whole query with separators removed, so "Tri X" still finds "Tri-X"
+        let (whole, _, _) = normalize(query);
+        if whole.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+        let whole = Needle::new(whole);
+
+        let terms: Vec<Needle> = query
+            .split_whitespace()
+            .map(|term| normalize(term).0)
+            .filter(|chars| !chars.is_empty())
+            .map(Needle::new)
+            .collect();
+
+        // Rank on scores alone and only build results for the entries that are returned
+        let mut scored: Vec<(u32, &IndexEntry, Vec<FieldHit>)> = self.entries
+            .iter()
+            .filter_map(|entry| entry.match_query(&whole, &terms).map(|(score, hits)| (score, entry, hits)))
+            .collect();
+
+        scored.sort_by(|(a_score, a, _), (b_score, b, _)| {
+            b_score.cmp(a_score)
+                .then_with(|| kind_rank(a.kind).cmp(&kind_rank(b.kind)))
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| a.key.cmp(&b.key))
+        });
+        scored.truncate(limit);
+
+        scored
+            .into_iter()
+            .map(|(score, entry, hits)| SearchResult {
+                kind: entry.kind,
+                key: entry.key.clone(),
+                name: entry.name.clone(),
+                manufacturer: entry.manufacturer.clone(),
+                score,
+                matches: entry.collect_matches(&hits),
+            })
+            .collect()
+    }
+}
+
+impl IndexEntry {
+    fn match_query(&self, whole: &Needle, terms: &[Needle]) -> Option<(u32, Vec<FieldHit>)> {
+        let whole_hit = self.best_hit(whole).map(|hit| (hit.score, vec![hit]));
+
+        // Multi-word queries may also match across fields, e.g. "ilford 400"
+        let terms_hit = if terms.len() > 1 {
+            let hits: Option<Vec<FieldHit>> = terms.iter().map(|term| self.best_hit(term)).collect();
+            hits.map(|hits| {
+                let score = hits.iter().map(|hit| hit.score).sum::<u32>() / hits.len() as u32;
+                (score, hits)
+            })
+        } else {
+            None
+        };
+
+        match (whole_hit, terms_hit) {
+            (Some(w), Some(t)) => Some(if t.0 > w.0 { t } else { w }),
+            (w, t) => w.or(t),
+        }
+    }
+
+    fn best_hit(&self, needle: &Needle) -> Option<FieldHit> {
+        // Typo-tolerant matching is only worth its cost when nothing matched as typed
+        self.best_field_hit(needle, IndexedField::match_exact)
+            .or_else(|| self.best_field_hit(needle, IndexedField::match_fuzzy))
+    }
+
+    fn best_field_hit(&self, needle: &Needle, matcher: fn(&IndexedField, &Needle) -> Option<FieldHit>) -> Option<FieldHit> {
+        self.fields
+            .iter()
+            .enumerate()
+            .filter_map(|(index, field)| {
+                matcher(field, needle).map(|mut hit| {
+                    hit.field_index = index;
+                    hit.score += field_bonus(field.field);
+                    hit
+                })
+            })
+            .max_by(|a, b| a.score.cmp(&b.score).then_with(|| b.field_index.cmp(&a.field_index)))
+    }
+
+    fn collect_matches(&self, hits: &[FieldHit]) -> Vec<SearchMatch> {
+        let mut by_field: HashMap<usize, SearchMatch> = HashMap::new();
+        let mut order = Vec::new();
+
+        for hit in hits {
+            let field = &self.fields[hit.field_index];
+            let highlight = HighlightRange {
+                start: field.offsets[hit.start],
+                end: field.offsets[hit.end - 1] + 1,
+            };
+
+            let entry = by_field.entry(hit.field_index).or_insert_with(|| {
+                order.push(hit.field_index);
+                SearchMatch {
+                    field: field.field,
+                    value: field.value.clone(),
+                    highlights: Vec::new(),
+                    typos: 0,
+                }
+            });
+            entry.highlights.push(highlight);
+            entry.typos += hit.typos;
+        }
+
+        order
+            .into_iter()
+            .filter_map(|index| by_field.remove(&index))
+            .map(|mut search_match| {
+                search_match.highlights.sort_by_key(|range| range.start);
+                search_match
+            })
+            .collect()
+    }
+}
+
+impl IndexedField {
+    fn new(field: SearchField, value: &str) -> Self {
+        let (chars, offsets, word_starts) = normalize(value);
+        let mask = char_mask(&chars);
+        Self {
+            field,
+            value: value.to_string(),
+            chars,
+            offsets,
+            word_starts,
+            mask,
+        }
+    }
+
+    fn match_exact(&self, needle: &Needle) -> Option<FieldHit> {
+        let text = &self.chars;
+        let query = &needle.chars;
+        if query.len() > text.len() || needle.mask & !self.mask != 0 {
+            return None;
+        }
+
+        if text == query {
+            return Some(FieldHit::exact(0, text.len(), SCORE_EXACT));
+        }
+        if text.starts_with(query) {
+            return Some(FieldHit::exact(0, query.len(), SCORE_PREFIX));
+        }
+        if let Some(&start) = self.word_starts.iter().find(|&&start| text[start..].starts_with(query)) {
+            return Some(FieldHit::exact(start, start + query.len(), SCORE_WORD_PREFIX));
+        }
+        if query.len() >= 3 {
+            if let Some(start) = text.windows(query.len()).position(|window| window == query.as_slice()) {
+                return Some(FieldHit::exact(start, start + query.len(), SCORE_SUBSTRING));
+            }
+        }
+
+        None
+    }
+
+    fn match_fuzzy(&self, needle: &Needle) -> Option<FieldHit> {
+        let query = &needle.chars;
+        let allowed = max_typos(query.len());
+        if allowed == 0 || query.len() > self.chars.len() + allowed {
+            return None;
+        }
+
+        // Every character of the query missing from the field costs at least one edit
+        if (needle.mask & !self.mask).count_ones() as usize > allowed {
+            return None;
+        }
+
+        let mut best: Option<(usize, usize, usize)> = None;
+        for &start in &self.word_starts {
+            let limit = best.map_or(allowed, |(typos, _, _)| typos);
+            let end = (start + query.len() + allowed).min(self.chars.len());
+            if let Some((typos, len)) = bounded_prefix_distance(query, &self.chars[start..end], limit) {
+                if best.is_none_or(|(current, _, _)| typos < current) {
+                    best = Some((typos, start, start + len));
+                }
+            }
+        }
+
+        best.map(|(typos, start, end)| FieldHit {
+            field_index: 0,
+            start,
+            end,
+            typos: typos as u32,
+            score: SCORE_FUZZY - FUZZY_PENALTY_PER_TYPO * typos as u32,
+        })
+    }
+}
+
+impl FieldHit {
+    fn exact(start: usize, end: usize, score: u32) -> Self {
+        Self { field_index: 0, start, end, typos: 0, score }
+    }
+}
+
+impl Needle {
+    fn new(chars: Vec<char>) -> Self {
+        let mask = char_mask(&chars);
+        Self { chars, mask }
+    }
+}
+
+fn normalize(value: &str) -> (Vec<char>, Vec<usize>, Vec<usize>) {
+    let mut chars = Vec::with_capacity(value.len());
+    let mut offsets = Vec::with_capacity(value.len());
+    let mut word_starts = Vec::new();
+    let mut previous: Option<char> = None;
+
+    for (offset, c) in value.chars().enumerate() {
+        if c.is_alphanumeric() {
+            // A new word starts after a separator or where letters and digits meet ("HP5", "D76")
+            let starts_word = match previous {
+                Some(prev) => prev.is_numeric() != c.is_numeric(),
+                None => true,
+            };
+            if starts_word {
+                word_starts.push(chars.len());
+            }
+            for lower in c.to_lowercase() {
+                chars.push(lower);
+                offsets.push(offset);
+            }
+            previous = Some(c);
+        } else {
+            previous = None;
+        }
+    }
+
+    (chars, offsets, word_starts)
+}
+
+fn char_mask(chars: &[char]) -> u64 {
+    chars.iter().fold(0u64, |mask, &c| mask | 1u64 << (c as u32 % 64))
+}
+
+fn max_typos(len: usize) -> usize {
+    match len {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+fn field_bonus(field: SearchField) -> u32 {
+    match field {
+        SearchField::Name => 10,
+        SearchField::AlternativeName => 8,
+        SearchField::Iso => 5,
+        SearchField::Key => 4,
+        SearchField::Manufacturer => 2,
+    }
+}
+
+fn kind_rank(kind: CatalogueKind) -> u8 {
+    match kind {
+        CatalogueKind::Film => 0,
+        CatalogueKind::Developer => 1,
+    }
+}
+
+// Optimal string alignment distance (Levenshtein plus adjacent transpositions)
+// between `query` and the closest prefix of `text`, returning the distance and
+// the prefix length. Gives up as soon as the distance is certain to exceed `limit`.
+fn bounded_prefix_distance(query: &[char], text: &[char], limit: usize) -> Option<(usize, usize)> {
+    const MAX_LEN: usize = 64;
+    if query.is_empty() || query.len() >= MAX_LEN || text.len() >= MAX_LEN {
+        return None;
+    }
+
+    let width = text.len() + 1;
+    let mut before = [0usize; MAX_LEN];
+    let mut previous = [0usize; MAX_LEN];
+    let mut current = [0usize; MAX_LEN];
+    for (j, cell) in previous.iter_mut().enumerate().take(width) {
+        *cell = j;
+    }
+
+    for i in 1..=query.len() {
+        current[0] = i;
+        let mut row_min = current[0];
+        for j in 1..width {
+            let cost = if query[i - 1] == text[j - 1] { 0 } else { 1 };
+            let mut value = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && query[i - 1] == text[j - 2] && query[i - 2] == text[j - 1] {
+                value = value.min(before[j - 2] + 1);
+            }
+            current[j] = value;
+            row_min = row_min.min(value);
+        }
+        if row_min > limit {
+            return None;
+        }
+        std::mem::swap(&mut before, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    // Prefer the fewest edits, then the prefix closest in length to the query
+    (1..width)
+        .filter(|&len| previous[len] <= limit)
+        .min_by_key(|&len| (previous[len], len.abs_diff(query.len())))
+        .map(|len| (previous[len], len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(text: &str) -> Vec<char> {
+        text.chars().collect()
+    }
+
+    // An index over one film, for names the shipped database does not have
+    fn film_index(name: &str) -> SearchIndex {
+        SearchIndex {
+            entries: vec![IndexEntry {
+                kind: CatalogueKind::Film,
+                key: "test_film".to_string(),
+                name: name.to_string(),
+                manufacturer: String::new(),
+                fields: vec![IndexedField::new(SearchField::Name, name)],
+            }],
+        }
+    }
+
+    fn name_highlights(result: &SearchResult) -> Vec<(usize, usize)> {
+        result.matches
+            .iter()
+            .filter(|search_match| search_match.field == SearchField::Name)
+            .flat_map(|search_match| search_match.highlights.iter().map(|range| (range.start, range.end)))
+            .collect()
+    }
+
+    #[test]
+    fn osa_distance_counts_a_transposition_as_one_edit() {
+        assert_eq!(bounded_prefix_distance(&chars("acros"), &chars("acros"), 1), Some((0, 5)));
+        assert_eq!(bounded_prefix_distance(&chars("arcos"), &chars("acros"), 1), Some((1, 5)));
+        assert_eq!(bounded_prefix_distance(&chars("acors"), &chars("acros100"), 1), Some((1, 5)));
+        assert_eq!(bounded_prefix_distance(&chars("tirx"), &chars("trix400"), 1), Some((1, 4)));
+        assert_eq!(bounded_prefix_distance(&chars("xxxxx"), &chars("acros"), 2), None);
+    }
+
+    #[test]
+    fn short_queries_must_match_as_typed() {
+        assert_eq!(max_typos(3), 0);
+        assert_eq!(max_typos(4), 1);
+        assert_eq!(max_typos(8), 2);
+    }
+
+    #[test]
+    fn prefilter_rejects_fields_missing_too_many_query_characters() {
+        let field = IndexedField::new(SearchField::Name, "Kodak Tri-X 400");
+        assert!(field.match_fuzzy(&Needle::new(chars("trix"))).is_some());
+        assert!(field.match_fuzzy(&Needle::new(chars("qwzj"))).is_none());
+        assert!(field.match_exact(&Needle::new(chars("qwzj"))).is_none());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn typos_in_common_film_names_still_find_the_film() {
+        let engine = crate::test_support::engine();
+        for (query, expected) in [
+            ("HP5", "ilford_hp5_plus"),
+            ("hp5 plsu", "ilford_hp5_plus"),
+            ("Ilford HP5+", "ilford_hp5_plus"),
+            ("Tri-X", "kodak_tri_x_400"),
+            ("tri x", "kodak_tri_x_400"),
+            ("Tirx", "kodak_tri_x_400"),
+            ("Trix 400", "kodak_tri_x_400"),
+            ("Acros", "fuji_acros_100"),
+            ("Acors", "fuji_acros_100"),
+            ("neopan across", "fuji_acros_100"),
+        ] {
+            let results = engine.search_catalogue(query, 5).unwrap();
+            assert_eq!(results.first().map(|result| result.key.as_str()), Some(expected), "{}", query);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn exact_matches_rank_above_prefixes_and_typos() {
+        let engine = crate::test_support::engine();
+        let results = engine.search_catalogue("Delta 400", 10).unwrap();
+        assert_eq!(results[0].key, "ilford_delta_400");
+
+        let results = engine.search_catalogue("acros", 10).unwrap();
+        let typos: Vec<u32> = results
+            .iter()
+            .map(|result| result.matches.iter().map(|search_match| search_match.typos).sum())
+            .collect();
+        assert_eq!(results[0].key, "fuji_acros_100");
+        assert_eq!(results[1].key, "fuji_acros_ii_100");
+        assert!(typos[..2].iter().all(|&typos| typos == 0));
+        assert!(results.windows(2).all(|pair| pair[0].score >= pair[1].score));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn films_rank_before_developers_on_equal_scores() {
+        let engine = crate::test_support::engine();
+        let results = engine.search_catalogue("ilford", 50).unwrap();
+        let first_developer = results.iter().position(|result| result.kind == CatalogueKind::Developer).unwrap();
+        assert!(results[..first_developer].iter().all(|result| result.kind == CatalogueKind::Film));
+        assert!(results[first_developer..].iter().all(|result| {
+            result.kind == CatalogueKind::Developer || result.score < results[first_developer].score
+        }));
+    }
+
+    #[test]
+    fn highlights_are_character_offsets_in_multi_byte_names() {
+        let index = film_index("Fotokemika Ñandú Pan 100");
+
+        let results = index.search("ñandú", 5);
+        assert_eq!(name_highlights(&results[0]), vec![(11, 16)]);
+
+        let results = index.search("ñandú 100", 5);
+        assert_eq!(name_highlights(&results[0]), vec![(11, 16), (21, 24)]);
+    }
+
+    #[test]
+    fn empty_queries_and_limits_return_nothing() {
+        let index = film_index("Kodak Tri-X 400");
+        assert!(index.search("", 5).is_empty());
+        assert!(index.search(" - ", 5).is_empty());
+        assert!(index.search("tri-x", 0).is_empty());
+    }
+}
@@ -0,0 +1,273 @@
+use crate::calculator::{CalculationEngine, CalculationError};
+use crate::models::*;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+const DEFAULT_TIME_TOLERANCE_SECONDS: u32 = 30;
+const DEFAULT_MAX_ROLLS_PER_TANK: u32 = 2;
+// Exposure indexes further than this from a whole stop get a note
+const STOP_ROUNDING_NOTE_THRESHOLD: f64 = 0.17;
+
+struct PlannedRoll {
+    developer_key: String,
+    film_type: FilmType,
+    dilution: String,
+    result: SessionRollResult,
+}
+
+impl CalculationEngine {
+    pub fn plan_session(&self, request: &SessionRequest) -> Result<SessionPlan, CalculationError> {
+        let database = self.get_database()?;
+        let developer = self.find_developer(&database.developers, &request.developer_key)?;
+        let tolerance = Decimal::from(request.time_tolerance_seconds.unwrap_or(DEFAULT_TIME_TOLERANCE_SECONDS)) / Decimal::from(60);
+        let max_rolls = request.max_rolls_per_tank.unwrap_or(DEFAULT_MAX_ROLLS_PER_TANK).max(1) as usize;
+
+        // A dilution key such as "kodak_hc110_b" fixes the dilution for every roll
+        let is_combination_key = database.films
+            .values()
+            .any(|film| film.developers.contains_key(&request.developer_key));
+
+        // Otherwise rolls prefer the dilution most other rolls can use, so they can share tanks
+        let mut dilution_support: HashMap<&String, usize> = HashMap::new();
+        if !is_combination_key {
+            for roll in &request.rolls {
+                if let Some(film) = database.films.get(&roll.film_key) {
+                    for key in film.developers.keys() {
+                        if self.is_same_developer(key, developer) {
+                            *dilution_support.entry(key).or_insert(0) += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut flagged_rolls = Vec::new();
+        let mut planned = Vec::new();
+        for (index, roll) in request.rolls.iter().enumerate() {
+            let label = roll.label.clone().unwrap_or_else(|| format!("Roll {}", index + 1));
+            let developer_key = if is_combination_key {
+                Some(request.developer_key.clone())
+            } else {
+                database.films.get(&roll.film_key).and_then(|film| {
+                    film.developers
+                        .keys()
+                        .filter(|key| self.is_same_developer(key, developer))
+                        .max_by(|a, b| {
+                            dilution_support.get(a).cmp(&dilution_support.get(b))
+                                .then_with(|| b.cmp(a))
+                        })
+                        .cloned()
+                })
+            };
+
+            let planned_roll = match developer_key {
+                Some(developer_key) => self.plan_roll(index, &label, roll, developer_key, request.temperature),
+                None => Err(match database.films.get(&roll.film_key) {
+                    Some(film) => CalculationError::CombinationNotSupported {
+                        film: film.name.clone(),
+                        developer: request.developer_key.clone(),
+                    },
+                    None => CalculationError::FilmNotFound(roll.film_key.clone()),
+                }.to_string()),
+            };
+
+            match planned_roll {
+                Ok(planned_roll) => planned.push(planned_roll),
+                Err(reason) => flagged_rolls.push(FlaggedRoll {
+                    roll_index: index,
+                    label,
+                    film_key: roll.film_key.clone(),
+                    tank_number: None,
+                    reason,
+                }),
+            }
+        }
+
+        // Rolls can only share chemistry mixed at the same dilution
+        let mut groups: Vec<Vec<PlannedRoll>> = Vec::new();
+        for roll in planned {
+            match groups.iter_mut().find(|group| group[0].developer_key == roll.developer_key) {
+                Some(group) => group.push(roll),
+                None => groups.push(vec![roll]),
+            }
+        }
+
+        let mut tanks: Vec<SessionTank> = Vec::new();
+        for mut group in groups {
+            let first_tank = tanks.len();
+            group.sort_by_key(|roll| roll.result.time_minutes);
+            let group_times: Vec<Decimal> = group.iter().map(|roll| roll.result.time_minutes).collect();
+
+            let mut current: Vec<PlannedRoll> = Vec::new();
+            for roll in group {
+                // Every roll in a tank must be within the tolerance of the tank time, the midpoint
+                let fits = current.first().is_some_and(|first| {
+                    current.len() < max_rolls && roll.result.time_minutes - first.result.time_minutes <= tolerance * Decimal::from(2)
+                });
+                if !fits && !current.is_empty() {
+                    tanks.push(self.build_tank(tanks.len() + 1, std::mem::take(&mut current), developer));
+                }
+                current.push(roll);
+            }
+            if !current.is_empty() {
+                tanks.push(self.build_tank(tanks.len() + 1, current, developer));
+            }
+
+            // Flag rolls left alone for a reason other than tank capacity
+            if request.rolls.len() > 1 {
+                for tank in tanks[first_tank..].iter().filter(|tank| tank.rolls.len() == 1) {
+                    let roll = &tank.rolls[0];
+                    let partners = group_times.len() - 1;
+                    let compatible = group_times
+                        .iter()
+                        .filter(|&&time| (time - roll.time_minutes).abs() <= tolerance * Decimal::from(2))
+                        .count() - 1;
+
+                    let reason = if partners == 0 {
+                        Some(format!("No other roll is developed in {} {}", tank.developer_name, tank.dilution))
+                    } else if compatible == 0 {
+                        Some(format!(
+                            "Development time {} is more than {}s from every other roll in {} {}",
+                            self.format_time(roll.time_minutes),
+                            request.time_tolerance_seconds.unwrap_or(DEFAULT_TIME_TOLERANCE_SECONDS),
+                            tank.developer_name,
+                            tank.dilution
+                        ))
+                    } else {
+                        None
+                    };
+
+                    if let Some(reason) = reason {
+                        flagged_rolls.push(FlaggedRoll {
+                            roll_index: roll.roll_index,
+                            label: roll.label.clone(),
+                            film_key: roll.film_key.clone(),
+                            tank_number: Some(tank.tank_number),
+                            reason,
+                        });
+                    }
+                }
+            }
+        }
+        flagged_rolls.sort_by_key(|flag| flag.roll_index);
+
+        let mut concentrate_totals: Vec<ConcentrateTotal> = Vec::new();
+        for tank in &tanks {
+            match concentrate_totals.iter_mut().find(|total| total.dilution == tank.dilution) {
+                Some(total) => total.amount_ml += tank.developer_amount_ml,
+                None => concentrate_totals.push(ConcentrateTotal {
+                    developer_name: tank.developer_name.clone(),
+                    dilution: tank.dilution.clone(),
+                    amount_ml: tank.developer_amount_ml,
+                }),
+            }
+        }
+
+        Ok(SessionPlan {
+            temperature: request.temperature,
+            tanks,
+            flagged_rolls,
+            concentrate_totals,
+        })
+    }
+
+    fn is_same_developer(&self, combination_key: &str, developer: &Developer) -> bool {
+        self.get_database()
+            .ok()
+            .and_then(|database| self.find_developer(&database.developers, combination_key).ok())
+            .is_some_and(|other| std::ptr::eq(other, developer))
+    }
+
+    fn plan_roll(
+        &self,
+        index: usize,
+        label: &str,
+        roll: &SessionRoll,
+        developer_key: String,
+        temperature: Decimal,
+    ) -> Result<PlannedRoll, String> {
+        let film = self.get_film_info(&roll.film_key).map_err(|e| e.to_string())?;
+        if roll.exposure_index == 0 {
+            return Err("Exposure index must be greater than zero".to_string());
+        }
+
+        let mut notes = Vec::new();
+        let stops = (f64::from(roll.exposure_index) / f64::from(film.iso)).log2();
+        let push_pull = stops.round() as i32;
+        if (stops - f64::from(push_pull)).abs() > STOP_ROUNDING_NOTE_THRESHOLD {
+            notes.push(format!(
+                "EI {} is {:+.1} stops from box speed, developed as {:+}",
+                roll.exposure_index,
+                stops,
+                push_pull
+            ));
+        }
+
+        let volume_ml = roll.format.solution_volume_ml();
+        let result = self
+            .calculate_development(CalculationRequest {
+                film_key: roll.film_key.clone(),
+                developer_key: developer_key.clone(),
+                temperature,
+                push_pull,
+                volume: volume_ml,
+            })
+            .map_err(|e| e.to_string())?;
+
+        let dilution = film.developers
+            .get(&developer_key)
+            .map(|data| data.dilution.clone())
+            .unwrap_or_default();
+
+        Ok(PlannedRoll {
+            developer_key,
+            film_type: film.film_type.clone(),
+            dilution,
+            result: SessionRollResult {
+                roll_index: index,
+                label: label.to_string(),
+                film_key: roll.film_key.clone(),
+                film_name: film.name.clone(),
+                exposure_index: roll.exposure_index,
+                format: roll.format,
+                push_pull,
+                time_minutes: result.time_minutes,
+                volume_ml,
+                notes,
+            },
+        })
+    }
+
+    fn build_tank(&self, tank_number: usize, rolls: Vec<PlannedRoll>, developer: &Developer) -> SessionTank {
+        let shortest = rolls.iter().map(|roll| roll.result.time_minutes).min().unwrap_or_default();
+        let longest = rolls.iter().map(|roll| roll.result.time_minutes).max().unwrap_or_default();
+        let time_minutes = ((shortest + longest) / Decimal::from(2) * Decimal::from(60)).round() / Decimal::from(60);
+
+        let max_deviation_seconds = rolls
+            .iter()
+            .map(|roll| ((roll.result.time_minutes - time_minutes).abs() * Decimal::from(60)).round())
+            .max()
+            .unwrap_or_default();
+
+        let volume_ml = rolls.iter().map(|roll| roll.result.volume_ml).sum();
+        let first = &rolls[0];
+        let (dilution, developer_amount_ml, water_amount_ml) = self
+            .calculate_dilution(&first.dilution, volume_ml, &first.film_type)
+            .unwrap_or(("Stock".to_string(), volume_ml, 0));
+
+        SessionTank {
+            tank_number,
+            developer_key: first.developer_key.clone(),
+            developer_name: developer.name.clone(),
+            dilution,
+            time_minutes: time_minutes.round_dp(2),
+            time_formatted: self.format_time(time_minutes),
+            volume_ml,
+            developer_amount_ml,
+            water_amount_ml,
+            max_deviation_seconds: max_deviation_seconds.to_u32().unwrap_or(u32::MAX),
+            rolls: rolls.into_iter().map(|roll| roll.result).collect(),
+        }
+    }
+}
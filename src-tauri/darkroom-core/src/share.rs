@@ -0,0 +1,226 @@
+use crate::calculator::{CalculationEngine, CalculationError};
+#[cfg(feature = "export")]
+use crate::export::{ExportError, ExportManager};
+use crate::models::*;
+#[cfg(feature = "export")]
+use qrcode::render::svg;
+#[cfg(feature = "export")]
+use qrcode::{EcLevel, QrCode};
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+// Starts every code and names its layout, so the layout can change later
+const CODE_PREFIX: &str = "DRP1";
+const FIELD_SEPARATOR: char = '|';
+// RFC 4648 base 32, which only uses characters QR codes store compactly
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+const CHECKSUM_BYTES: usize = 4;
+
+// Big enough to scan off a phone screen or a printout
+#[cfg(feature = "export")]
+const QR_SVG_MIN_SIZE: u32 = 240;
+#[cfg(feature = "export")]
+const QR_PNG_MODULE_PIXELS: usize = 8;
+// Light border around the code that scanners need, in modules
+#[cfg(feature = "export")]
+const QR_QUIET_ZONE: usize = 4;
+
+#[derive(Error, Debug)]
+pub enum ShareCodeError {
+    #[error("Not a DarkroomPro recipe code")]
+    InvalidCode,
+    #[error("The recipe code is damaged or mistyped, its checksum does not match")]
+    ChecksumMismatch,
+    #[error("The recipe code is for database version {code}, this database is version {database}")]
+    IncompatibleDatabase { code: String, database: String },
+    #[error(transparent)]
+    CalculationError(#[from] CalculationError),
+}
+
+impl CalculationEngine {
+    /// Calculates the recipe first so only working recipes are handed out
+    pub fn share_code(&self, request: &CalculationRequest) -> Result<ShareCode, ShareCodeError> {
+        let database = self.get_database()?;
+        let calculation = self.calculate_development(request.clone())?;
+
+        Ok(ShareCode {
+            code: encode_share_code(request, &database.metadata.version),
+            database_version: database.metadata.version.clone(),
+            film_name: calculation.film_name,
+            developer_name: calculation.developer_name,
+        })
+    }
+
+    /// Codes only work within one major database version, keys may change between them
+    pub fn load_share_code(&self, code: &str) -> Result<SharedRecipe, ShareCodeError> {
+        let database = self.get_database()?;
+        let (database_version, request) = decode_share_code(code)?;
+        if major_version(&database_version) != major_version(&database.metadata.version) {
+            return Err(ShareCodeError::IncompatibleDatabase {
+                code: database_version,
+                database: database.metadata.version.clone(),
+            });
+        }
+
+        let calculation = self.calculate_development(request.clone())?;
+        Ok(SharedRecipe {
+            request,
+            database_version,
+            calculation,
+        })
+    }
+}
+
+#[cfg(feature = "export")]
+impl ExportManager {
+    pub fn export_share_qr(
+        &self,
+        share: &ShareCode,
+        format: QrFormat,
+        file_path: Option<String>,
+    ) -> Result<String, ExportError> {
+        let qr = QrCode::with_error_correction_level(&share.code, EcLevel::M)?;
+        let name = [share.film_name.as_str(), &share.developer_name];
+
+        match format {
+            QrFormat::Svg => {
+                let content = qr
+                    .render::<svg::Color>()
+                    .min_dimensions(QR_SVG_MIN_SIZE, QR_SVG_MIN_SIZE)
+                    .dark_color(svg::Color("#000000"))
+                    .light_color(svg::Color("#ffffff"))
+                    .build();
+                let path = self.write_file(file_path.as_deref(), &name, "svg", content.as_bytes())?;
+                Ok(format!("Exported recipe QR code to SVG file: {}", path))
+            },
+            QrFormat::Png => {
+                let path = self.write_file(file_path.as_deref(), &name, "png", &qr_png(&qr)?)?;
+                Ok(format!("Exported recipe QR code to PNG file: {}", path))
+            },
+        }
+    }
+}
+
+/// e.g. DRP1-GIXDA7DJNRTG64TEL5UH...
+pub fn encode_share_code(request: &CalculationRequest, database_version: &str) -> String {
+    let fields = [
+        database_version.to_string(),
+        request.film_key.clone(),
+        request.developer_key.clone(),
+        request.temperature.normalize().to_string(),
+        request.push_pull.to_string(),
+        request.volume.to_string(),
+    ];
+    let mut payload = fields.join(&FIELD_SEPARATOR.to_string()).into_bytes();
+    payload.extend(crc32(&payload).to_be_bytes());
+
+    format!("{}-{}", CODE_PREFIX, base32_encode(&payload))
+}
+
+/// The database version the code was made with and the recipe. Spaces, dashes
+/// and lower case are accepted so codes survive being typed in by hand.
+pub fn decode_share_code(code: &str) -> Result<(String, CalculationRequest), ShareCodeError> {
+    let cleaned: String = code
+        .chars()
+        .filter(|character| !character.is_whitespace() && *character != '-')
+        .map(|character| character.to_ascii_uppercase())
+        .collect();
+    let encoded = cleaned.strip_prefix(CODE_PREFIX).ok_or(ShareCodeError::InvalidCode)?;
+
+    let bytes = base32_decode(encoded).ok_or(ShareCodeError::InvalidCode)?;
+    if bytes.len() <= CHECKSUM_BYTES {
+        return Err(ShareCodeError::InvalidCode);
+    }
+    let (payload, checksum) = bytes.split_at(bytes.len() - CHECKSUM_BYTES);
+    if crc32(payload).to_be_bytes() != checksum {
+        return Err(ShareCodeError::ChecksumMismatch);
+    }
+
+    let payload = String::from_utf8(payload.to_vec()).map_err(|_| ShareCodeError::InvalidCode)?;
+    let fields: Vec<&str> = payload.split(FIELD_SEPARATOR).collect();
+    let [database_version, film_key, developer_key, temperature, push_pull, volume] = fields[..] else {
+        return Err(ShareCodeError::InvalidCode);
+    };
+
+    let request = CalculationRequest {
+        film_key: film_key.to_string(),
+        developer_key: developer_key.to_string(),
+        temperature: temperature.parse::<Decimal>().map_err(|_| ShareCodeError::InvalidCode)?,
+        push_pull: push_pull.parse().map_err(|_| ShareCodeError::InvalidCode)?,
+        volume: volume.parse().map_err(|_| ShareCodeError::InvalidCode)?,
+    };
+    Ok((database_version.to_string(), request))
+}
+
+fn major_version(version: &str) -> &str {
+    version.split('.').next().unwrap_or(version)
+}
+
+// Black on white, 8-bit greyscale
+#[cfg(feature = "export")]
+fn qr_png(qr: &QrCode) -> Result<Vec<u8>, ExportError> {
+    let modules = qr.width();
+    let size = (modules + 2 * QR_QUIET_ZONE) * QR_PNG_MODULE_PIXELS;
+    let mut pixels = vec![u8::MAX; size * size];
+
+    for (index, color) in qr.to_colors().iter().enumerate() {
+        if *color != qrcode::Color::Dark {
+            continue;
+        }
+        let left = (index % modules + QR_QUIET_ZONE) * QR_PNG_MODULE_PIXELS;
+        let top = (index / modules + QR_QUIET_ZONE) * QR_PNG_MODULE_PIXELS;
+        for y in top..top + QR_PNG_MODULE_PIXELS {
+            pixels[y * size + left..y * size + left + QR_PNG_MODULE_PIXELS].fill(0);
+        }
+    }
+
+    let mut content = Vec::new();
+    let mut encoder = png::Encoder::new(&mut content, size as u32, size as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(&pixels)?;
+    Ok(content)
+}
+
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len() * 8 / 5 + 1);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        encoded.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    encoded
+}
+
+fn base32_decode(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for character in text.bytes() {
+        let value = BASE32_ALPHABET.iter().position(|&symbol| symbol == character)?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+// CRC-32 as used by zip and PNG
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, &byte| {
+        (0..8).fold(crc ^ u32::from(byte), |crc, _| {
+            if crc & 1 != 0 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 }
+        })
+    })
+}
@@ -0,0 +1,186 @@
+use crate::calculator::{CalculationEngine, CalculationError};
+use crate::catalogue::summarize_film;
+use crate::models::*;
+
+const GRAIN_SCALE: [&str; 4] = ["ultra_fine", "fine", "medium", "medium_coarse"];
+const CONTRAST_SCALE: [&str; 4] = ["low", "medium", "medium_high", "high"];
+
+impl CalculationEngine {
+    pub fn find_substitutes(&self, recipe: &CalculationRequest, limit: Option<usize>) -> Result<SubstitutionResult, CalculationError> {
+        let database = self.get_database()?;
+
+        // Also validates the recipe against the original film
+        let original_recipe = self.calculate_development(recipe.clone())?;
+
+        let film = self.get_film_info(&recipe.film_key)?;
+        let developer = self.find_developer(&database.developers, &recipe.developer_key)?;
+        let discontinued_note = discontinued_note(&database.metadata.notes, film);
+
+        let mut substitutes: Vec<FilmSubstitute> = database.films
+            .iter()
+            .filter(|(key, candidate)| {
+                *key != &recipe.film_key && candidate.current_production && candidate.film_type == film.film_type
+            })
+            .filter_map(|(key, candidate)| {
+                // Prefer the same dilution, otherwise any dilution of the same developer
+                let developer_key = if candidate.developers.contains_key(&recipe.developer_key) {
+                    recipe.developer_key.clone()
+                } else {
+                    let mut keys: Vec<&String> = candidate.developers
+                        .keys()
+                        .filter(|key| {
+                            self.find_developer(&database.developers, key)
+                                .is_ok_and(|other| std::ptr::eq(other, developer))
+                        })
+                        .collect();
+                    keys.sort();
+                    keys.first().map(|key| (*key).clone())?
+                };
+
+                Some(self.build_substitute(recipe, film, key, candidate, developer_key))
+            })
+            .collect();
+
+        substitutes.sort_by(|a, b| {
+            b.similarity.cmp(&a.similarity)
+                .then_with(|| a.film.name.cmp(&b.film.name))
+        });
+        if let Some(limit) = limit {
+            substitutes.truncate(limit);
+        }
+
+        Ok(SubstitutionResult {
+            film_key: recipe.film_key.clone(),
+            film_name: film.name.clone(),
+            discontinued: !film.current_production || discontinued_note.is_some(),
+            discontinued_note,
+            original_recipe,
+            substitutes,
+        })
+    }
+
+    fn build_substitute(
+        &self,
+        recipe: &CalculationRequest,
+        film: &Film,
+        key: &str,
+        candidate: &Film,
+        developer_key: String,
+    ) -> FilmSubstitute {
+        let mut similarity = 0;
+        let mut reasons = Vec::new();
+        let mut notes = Vec::new();
+
+        let stops = (f64::from(candidate.iso) / f64::from(film.iso)).log2();
+        similarity += (40.0 - 15.0 * stops.abs()).max(0.0).round() as u32;
+        if candidate.iso == film.iso {
+            reasons.push(format!("Same ISO ({})", film.iso));
+        } else {
+            reasons.push(format!(
+                "ISO {} vs {} ({:.1} stop{} {})",
+                candidate.iso,
+                film.iso,
+                stops.abs(),
+                if (stops.abs() - 1.0).abs() < f64::EPSILON { "" } else { "s" },
+                if stops > 0.0 { "faster" } else { "slower" },
+            ));
+        }
+
+        similarity += scale_points(&GRAIN_SCALE, &film.grain, &candidate.grain, "grain", &mut reasons);
+        similarity += scale_points(&CONTRAST_SCALE, &film.contrast, &candidate.contrast, "contrast", &mut reasons);
+
+        let shared_uses: Vec<&String> = candidate.best_uses
+            .iter()
+            .filter(|use_case| film.best_uses.contains(use_case))
+            .collect();
+        if !shared_uses.is_empty() {
+            similarity += (5 * shared_uses.len() as u32).min(10);
+            reasons.push(format!(
+                "Shared uses: {}",
+                shared_uses.iter().map(|use_case| use_case.replace('_', " ")).collect::<Vec<_>>().join(", ")
+            ));
+        }
+
+        if developer_key == recipe.developer_key {
+            similarity += 10;
+            reasons.push("Published data for the same developer and dilution".to_string());
+        } else {
+            let dilution = &candidate.developers[&developer_key].dilution;
+            notes.push(format!("No data for the original dilution, using {}", dilution));
+        }
+
+        // Keep the exposure index the old roll was shot at
+        let exposure_index = f64::from(film.iso) * 2f64.powi(recipe.push_pull);
+        let push_pull = (exposure_index / f64::from(candidate.iso)).log2().round() as i32;
+
+        let starting_recipe = if (-2..=3).contains(&push_pull) {
+            if push_pull != 0 {
+                notes.push(format!(
+                    "Shoot at EI {} ({:+} stop{})",
+                    exposure_index.round(),
+                    push_pull,
+                    if push_pull.abs() == 1 { "" } else { "s" }
+                ));
+            }
+            let request = CalculationRequest {
+                film_key: key.to_string(),
+                developer_key: developer_key.clone(),
+                temperature: recipe.temperature,
+                push_pull,
+                volume: recipe.volume,
+            };
+            match self.calculate_development(request) {
+                Ok(result) => Some(result),
+                Err(e) => {
+                    notes.push(format!("No starting recipe: {}", e));
+                    None
+                },
+            }
+        } else {
+            notes.push(format!(
+                "Matching EI {} would need {:+} stops, outside the supported range",
+                exposure_index.round(),
+                push_pull
+            ));
+            None
+        };
+
+        FilmSubstitute {
+            film: summarize_film(key, candidate),
+            developer_key,
+            similarity: similarity.min(100),
+            reasons,
+            push_pull,
+            starting_recipe,
+            notes,
+        }
+    }
+}
+
+// The database notes name discontinued films in free text, e.g. "Fuji Pro 400H (discontinued 2021)"
+fn discontinued_note(notes: &DatabaseNotes, film: &Film) -> Option<String> {
+    notes.discontinued_films
+        .iter()
+        .find(|entry| {
+            let name = entry.split('(').next().unwrap_or(entry).trim();
+            name.eq_ignore_ascii_case(&film.name)
+                || film.alternative_names.iter().any(|alias| name.eq_ignore_ascii_case(alias))
+        })
+        .cloned()
+}
+
+fn scale_points(scale: &[&str], original: &str, candidate: &str, label: &str, reasons: &mut Vec<String>) -> u32 {
+    let position = |value: &str| scale.iter().position(|step| step.eq_ignore_ascii_case(value));
+
+    match (position(original), position(candidate)) {
+        (Some(a), Some(b)) if a == b => {
+            reasons.push(format!("Same {} ({})", label, original.replace('_', " ")));
+            20
+        },
+        (Some(a), Some(b)) if a.abs_diff(b) == 1 => {
+            reasons.push(format!("Similar {} ({} vs {})", label, candidate.replace('_', " "), original.replace('_', " ")));
+            10
+        },
+        _ => 0,
+    }
+}
@@ -0,0 +1,229 @@
+use crate::cues::clock;
+use crate::models::*;
+use minijinja::{path_loader, Environment, ErrorKind, UndefinedBehavior, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+// Suffixes that mark a file as a template, stripped to get the output extension
+const TEMPLATE_SUFFIXES: [&str; 3] = [".j2", ".jinja2", ".jinja"];
+
+#[derive(Error, Debug)]
+pub enum TemplateError {
+    #[error("Template not found: {0}")]
+    NotFound(String),
+    #[error("More than one template is named {0}: {1}")]
+    Ambiguous(String, String),
+    #[error("Failed to read templates: {0}")]
+    FileError(#[from] std::io::Error),
+    #[error("Failed to render template: {0}")]
+    RenderError(#[from] minijinja::Error),
+}
+
+/// Jinja-style templates dropped into a folder by the user. A template is named
+/// after the file it produces, so club-card.md.j2 is "club-card.md" and
+/// club-card.html.j2 next to it is "club-card.html".
+#[derive(Debug, Clone)]
+pub struct TemplateLibrary {
+    directory: PathBuf,
+}
+
+impl TemplateLibrary {
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory }
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    pub fn list(&self) -> Result<Vec<ExportTemplate>, TemplateError> {
+        if !self.directory.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut templates = Vec::new();
+        for entry in fs::read_dir(&self.directory)? {
+            let entry = entry?;
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            // Hidden files can't be loaded, see minijinja::path_loader
+            if file_name.starts_with('.') || !entry.file_type()?.is_file() {
+                continue;
+            }
+
+            let output_name = TEMPLATE_SUFFIXES
+                .iter()
+                .find_map(|suffix| file_name.strip_suffix(suffix))
+                .unwrap_or(&file_name);
+            let extension = output_name
+                .split_once('.')
+                .and_then(|(_, rest)| rest.rsplit('.').next())
+                .map(str::to_string);
+            templates.push(ExportTemplate {
+                name: output_name.to_string(),
+                extension,
+                file_name,
+            });
+        }
+
+        templates.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.file_name.cmp(&b.file_name)));
+        Ok(templates)
+    }
+
+    pub fn render(&self, name: &str, data: &ExportData) -> Result<String, TemplateError> {
+        let template = self.find(name)?;
+
+        let mut environment = Environment::new();
+        // Loading through the folder lets templates include and extend each other
+        environment.set_loader(path_loader(&self.directory));
+        environment.set_keep_trailing_newline(true);
+        // Typos fail loudly, but `{% if plan %}` still works when there is no plan
+        environment.set_undefined_behavior(UndefinedBehavior::SemiStrict);
+        add_filters(&mut environment);
+
+        let rendered = environment
+            .get_template(&template.file_name)?
+            .render(Value::from_serialize(data))?;
+        Ok(rendered)
+    }
+
+    /// The template with the given name. club-card.md.j2 and club-card.md.jinja
+    /// both produce club-card.md, so rather than guess, neither can be used
+    /// until one is renamed.
+    pub fn find(&self, name: &str) -> Result<ExportTemplate, TemplateError> {
+        let mut matching: Vec<ExportTemplate> = self.list()?.into_iter().filter(|template| template.name == name).collect();
+        match matching.len() {
+            0 => Err(TemplateError::NotFound(name.to_string())),
+            1 => Ok(matching.remove(0)),
+            _ => Err(TemplateError::Ambiguous(
+                name.to_string(),
+                matching.iter().map(|template| template.file_name.as_str()).collect::<Vec<_>>().join(", "),
+            )),
+        }
+    }
+}
+
+// Unit formatting on top of the built-in filters, e.g.
+// {{ step.duration_seconds | clock }} or {{ calculation.temperature | fahrenheit }}
+fn add_filters(environment: &mut Environment) {
+    environment.add_filter("clock", |seconds: Value| -> Result<String, minijinja::Error> {
+        Ok(clock(number(&seconds)?.round().max(0.0) as u32))
+    });
+    environment.add_filter("fahrenheit", |celsius: Value| -> Result<String, minijinja::Error> {
+        Ok(trim_number(number(&celsius)? * 9.0 / 5.0 + 32.0, 1))
+    });
+    environment.add_filter("fl_oz", |ml: Value| -> Result<String, minijinja::Error> {
+        Ok(trim_number(number(&ml)? / 29.5735, 1))
+    });
+    environment.add_filter(
+        "decimal",
+        |value: Value, places: Option<usize>, separator: Option<String>| -> Result<String, minijinja::Error> {
+            let formatted = format!("{:.*}", places.unwrap_or(1), number(&value)?);
+            Ok(match separator {
+                Some(separator) => formatted.replace('.', &separator),
+                None => formatted,
+            })
+        },
+    );
+    environment.add_filter("agitation", |schedule: Value| -> Result<String, minijinja::Error> {
+        if schedule.is_none() || schedule.is_undefined() {
+            return Ok("None".to_string());
+        }
+        let schedule: AgitationSchedule = serde_json::to_value(&schedule)
+            .and_then(serde_json::from_value)
+            .map_err(|e| minijinja::Error::new(ErrorKind::InvalidOperation, "not an agitation schedule").with_source(e))?;
+        Ok(schedule.describe())
+    });
+}
+
+// Decimals are serialized as strings, so accept both
+fn number(value: &Value) -> Result<f64, minijinja::Error> {
+    match value.as_str() {
+        Some(text) => text
+            .trim()
+            .parse()
+            .map_err(|_| minijinja::Error::new(ErrorKind::InvalidOperation, format!("{:?} is not a number", text))),
+        None => f64::try_from(value.clone()),
+    }
+}
+
+// Rounded to at most the given places, without trailing zeros
+fn trim_number(value: f64, places: usize) -> String {
+    let formatted = format!("{:.*}", places, value);
+    if formatted.contains('.') {
+        formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        formatted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::EXPORT_FORMAT_VERSION;
+    use crate::test_support::engine;
+    use rust_decimal::Decimal;
+
+    // A fresh folder per test, so tests running in parallel never share files
+    fn library(name: &str, files: &[(&str, &str)]) -> TemplateLibrary {
+        let directory = std::env::temp_dir().join(format!("darkroom-templates-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        for (file_name, content) in files {
+            fs::write(directory.join(file_name), content).unwrap();
+        }
+        TemplateLibrary::new(directory)
+    }
+
+    fn export_data() -> ExportData {
+        let request = CalculationRequest {
+            film_key: "ilford_hp5_plus".to_string(),
+            developer_key: "adox_rodinal_1_50".to_string(),
+            temperature: Decimal::from(20),
+            push_pull: 0,
+            volume: 500,
+        };
+        ExportData {
+            version: EXPORT_FORMAT_VERSION,
+            calculation: engine().calculate_development(request.clone()).unwrap(),
+            request: Some(request),
+            plan: None,
+            safety_warnings: Vec::new(),
+            timestamp: "2026-01-01 12:00:00 UTC".to_string(),
+            format: ExportFormat::Template("wiki.md".to_string()),
+        }
+    }
+
+    #[test]
+    fn templates_are_named_after_the_file_they_produce() {
+        let library = library("names", &[("wiki.md.j2", ""), ("wiki.html.jinja", ""), ("notes.txt", ""), (".hidden.j2", "")]);
+        let templates = library.list().unwrap();
+
+        let names: Vec<(&str, Option<&str>)> =
+            templates.iter().map(|template| (template.name.as_str(), template.extension.as_deref())).collect();
+        assert_eq!(names, vec![("notes.txt", Some("txt")), ("wiki.html", Some("html")), ("wiki.md", Some("md"))]);
+    }
+
+    #[test]
+    fn templates_sharing_a_stem_each_render() {
+        let library = library("stem", &[("wiki.md.j2", "# {{ calculation.film_name }}"), ("wiki.html.j2", "<h1>{{ calculation.film_name }}</h1>")]);
+        let data = export_data();
+
+        assert_eq!(library.render("wiki.md", &data).unwrap(), format!("# {}", data.calculation.film_name));
+        assert_eq!(library.render("wiki.html", &data).unwrap(), format!("<h1>{}</h1>", data.calculation.film_name));
+        assert!(matches!(library.render("wiki", &data), Err(TemplateError::NotFound(_))));
+    }
+
+    #[test]
+    fn templates_producing_the_same_file_are_not_guessed_between() {
+        let library = library("ambiguous", &[("wiki.md.j2", "one"), ("wiki.md.jinja", "two")]);
+
+        match library.render("wiki.md", &export_data()) {
+            Err(TemplateError::Ambiguous(name, files)) => {
+                assert_eq!(name, "wiki.md");
+                assert_eq!(files, "wiki.md.j2, wiki.md.jinja");
+            }
+            other => panic!("expected an ambiguous template, got {:?}", other.map(|_| ())),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+// Shared by the unit tests: the database the app ships with, and an engine loaded with it
+use crate::calculator::CalculationEngine;
+use crate::database::DatabaseManager;
+use crate::models::Database;
+use std::sync::OnceLock;
+
+static DATABASE: OnceLock<Database> = OnceLock::new();
+
+pub(crate) fn database() -> Database {
+    DATABASE
+        .get_or_init(|| {
+            let mut db_manager = DatabaseManager::new();
+            db_manager
+                .load_from_json(include_str!("../../../complete_database.json"))
+                .expect("shipped database loads");
+            db_manager.take_database().expect("database was loaded")
+        })
+        .clone()
+}
+
+pub(crate) fn engine() -> CalculationEngine {
+    engine_with(database())
+}
+
+pub(crate) fn engine_with(database: Database) -> CalculationEngine {
+    let mut engine = CalculationEngine::new();
+    engine.load_database(database);
+    engine
+}
@@ -0,0 +1,892 @@
+use crate::models::*;
+#[cfg(feature = "serde")]
+use crate::timer_store::{TimerStore, TimerStoreError};
+#[cfg(feature = "serde")]
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "serde")]
+use std::thread;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+#[cfg(feature = "serde")]
+pub const TIMER_EVENT: &str = "process-timer";
+
+const DEFAULT_LEAD_IN_SECONDS: u32 = 10;
+// How often the worker checks for due cues, so cues arrive at most this late
+#[cfg(feature = "serde")]
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Error, Debug)]
+pub enum TimerError {
+    #[error("Process plan has no steps")]
+    EmptyPlan,
+    #[error("A process timer named {0} is already running")]
+    NameInUse(String),
+    #[error("No process timer named {0}")]
+    NotFound(String),
+    #[error("Timer is not running")]
+    NotRunning,
+    #[error("Timer is not paused")]
+    NotPaused,
+    #[cfg(feature = "serde")]
+    #[error("Timer changed but could not be saved: {0}")]
+    SaveFailed(#[from] TimerStoreError),
+    #[cfg(feature = "serde")]
+    #[error("Saved timers could not be restored: {0}")]
+    RestoreFailed(String),
+}
+
+/// Runs one process plan against a monotonic clock. Callers pass the current
+/// instant in, so the timer itself never sleeps or reads the clock.
+pub struct ProcessTimer {
+    name: String,
+    plan: ProcessPlan,
+    actions: Vec<ProcessAction>,
+    lead_in: Duration,
+    state: TimerState,
+    // Process time up to the last pause, skip or restore
+    base: Duration,
+    // When the clock last started moving, unset while the timer is stopped
+    resumed_at: Option<Instant>,
+    // Earliest process time whose cues have not been sent yet
+    cursor: Duration,
+}
+
+impl ProcessTimer {
+    pub fn start(request: TimerRequest, now: Instant) -> Result<(Self, TimerEvent), TimerError> {
+        if request.plan.steps.is_empty() {
+            return Err(TimerError::EmptyPlan);
+        }
+
+        let name = request.name.unwrap_or_else(|| request.plan.name.clone());
+        let lead_in_seconds = request.lead_in_seconds.unwrap_or(DEFAULT_LEAD_IN_SECONDS);
+        let timer = Self::restore(name, request.plan, lead_in_seconds, TimerState::Running, Duration::ZERO, now);
+        let event = timer.event(TimerEventKind::Started, None, now);
+        Ok((timer, event))
+    }
+
+    /// Rebuilds a timer at a known process time, e.g. from a snapshot saved before a restart.
+    /// Cues that fell due before that time are not sent again.
+    pub fn restore(
+        name: String,
+        plan: ProcessPlan,
+        lead_in_seconds: u32,
+        state: TimerState,
+        elapsed: Duration,
+        now: Instant,
+    ) -> Self {
+        let elapsed = elapsed.min(seconds(plan.total_seconds()));
+        ProcessTimer {
+            name,
+            actions: plan.actions(),
+            plan,
+            lead_in: seconds(lead_in_seconds),
+            state,
+            base: elapsed,
+            resumed_at: (state == TimerState::Running).then_some(now),
+            cursor: if elapsed.is_zero() { elapsed } else { elapsed + Duration::from_nanos(1) },
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn plan(&self) -> &ProcessPlan {
+        &self.plan
+    }
+
+    pub fn lead_in_seconds(&self) -> u32 {
+        self.lead_in.as_secs() as u32
+    }
+
+    pub fn state(&self) -> TimerState {
+        self.state
+    }
+
+    pub fn is_active(&self) -> bool {
+        matches!(self.state, TimerState::Running | TimerState::Paused)
+    }
+
+    /// Cues that fell due since the last poll, in time order
+    pub fn poll(&mut self, now: Instant) -> Vec<TimerEvent> {
+        if self.state != TimerState::Running {
+            return Vec::new();
+        }
+
+        let elapsed = self.elapsed(now);
+        let total = seconds(self.plan.total_seconds());
+        let mut cues: Vec<(Duration, TimerEventKind, Option<ProcessAction>)> = Vec::new();
+
+        for start in self.plan.step_starts().into_iter().skip(1) {
+            let start = seconds(start);
+            if self.is_due(start, elapsed) {
+                cues.push((start, TimerEventKind::PhaseChanged, None));
+            }
+        }
+
+        for action in &self.actions {
+            let start = seconds(action.start_seconds);
+            if action.kind != ProcessActionKind::Agitate && start >= self.lead_in {
+                let lead_in = start - self.lead_in;
+                if self.is_due(lead_in, elapsed) {
+                    cues.push((lead_in, TimerEventKind::LeadIn, Some(action.clone())));
+                }
+            }
+            if self.is_due(start, elapsed) {
+                cues.push((start, TimerEventKind::ActionDue, Some(action.clone())));
+            }
+        }
+
+        // Phase changes come before the pour they start
+        cues.sort_by_key(|(at, kind, _)| (*at, *kind != TimerEventKind::PhaseChanged));
+
+        let ticked = elapsed.as_secs() > self.cursor.as_secs();
+        self.cursor = elapsed + Duration::from_nanos(1);
+
+        let mut events: Vec<TimerEvent> = cues
+            .into_iter()
+            .map(|(_, kind, action)| self.event(kind, action, now))
+            .collect();
+
+        if elapsed >= total {
+            self.stop(now);
+            self.state = TimerState::Completed;
+            events.push(self.event(TimerEventKind::Completed, None, now));
+        } else if ticked {
+            events.push(self.event(TimerEventKind::Tick, None, now));
+        }
+
+        events
+    }
+
+    pub fn pause(&mut self, now: Instant) -> Result<Vec<TimerEvent>, TimerError> {
+        let mut events = self.flush(now)?;
+        self.stop(now);
+        self.state = TimerState::Paused;
+        events.push(self.event(TimerEventKind::Paused, None, now));
+        Ok(events)
+    }
+
+    pub fn resume(&mut self, now: Instant) -> Result<Vec<TimerEvent>, TimerError> {
+        if self.state != TimerState::Paused {
+            return Err(TimerError::NotPaused);
+        }
+
+        self.resumed_at = Some(now);
+        self.state = TimerState::Running;
+        Ok(vec![self.event(TimerEventKind::Resumed, None, now)])
+    }
+
+    /// Ends the current phase now and moves straight to the next one
+    pub fn skip(&mut self, now: Instant) -> Result<Vec<TimerEvent>, TimerError> {
+        let mut events = self.flush(now)?;
+        if !self.is_active() {
+            return Ok(events);
+        }
+
+        let elapsed = self.elapsed(now);
+        let phase_end = seconds(self.phase_end(self.phase_index(elapsed)));
+        self.base = phase_end;
+        self.resumed_at = Some(now);
+        // Cues inside the skipped time are dropped, the ones at the phase boundary still fire
+        self.cursor = phase_end;
+
+        events.push(self.event(TimerEventKind::Skipped, None, now));
+        events.extend(self.poll(now));
+        Ok(events)
+    }
+
+    /// Lengthens or shortens the current phase, never below the time already spent in it
+    pub fn adjust(&mut self, now: Instant, delta_seconds: i32) -> Result<Vec<TimerEvent>, TimerError> {
+        let mut events = self.flush(now)?;
+        if !self.is_active() {
+            return Ok(events);
+        }
+
+        let elapsed = self.elapsed(now);
+        let index = self.phase_index(elapsed);
+        let phase_start = seconds(self.phase_end(index) - self.plan.steps[index].duration_seconds);
+        let spent = (elapsed - phase_start).as_secs_f64().ceil() as u32;
+
+        let drain_pending = self.drain(index).is_some_and(|drain| seconds(drain.start_seconds) >= self.cursor);
+
+        let step = &mut self.plan.steps[index];
+        // A phase that drains keeps at least a second after its pour for the drain cue
+        let shortest = if step.drain_seconds > 0 { step.pour_seconds + 1 } else { 1 };
+        step.duration_seconds = step.duration_seconds
+            .saturating_add_signed(delta_seconds)
+            .max(spent)
+            .max(shortest);
+        self.actions = self.plan.actions();
+
+        events.push(self.event(TimerEventKind::Adjusted, None, now));
+        // Shortened so far that the drain should already have started, so it is due now
+        if let Some(drain) = self.drain(index).filter(|drain| drain_pending && seconds(drain.start_seconds) < self.cursor) {
+            events.push(self.event(TimerEventKind::ActionDue, Some(drain), now));
+        }
+        events.extend(self.poll(now));
+        Ok(events)
+    }
+
+    pub fn cancel(&mut self, now: Instant) -> Result<Vec<TimerEvent>, TimerError> {
+        if !self.is_active() {
+            return Err(TimerError::NotRunning);
+        }
+
+        let mut events = self.poll(now);
+        if self.state == TimerState::Completed {
+            return Ok(events);
+        }
+
+        self.stop(now);
+        self.state = TimerState::Cancelled;
+        events.push(self.event(TimerEventKind::Cancelled, None, now));
+        Ok(events)
+    }
+
+    pub fn status(&self, now: Instant) -> TimerStatus {
+        let elapsed = self.elapsed(now);
+        let index = self.phase_index(elapsed);
+        let step = &self.plan.steps[index];
+        let phase_end = self.phase_end(index);
+        let total = seconds(self.plan.total_seconds());
+        let elapsed_seconds = elapsed.as_secs() as u32;
+        let next_action = if self.is_active() {
+            self.actions.iter().find(|action| seconds(action.start_seconds) >= elapsed).cloned()
+        } else {
+            None
+        };
+
+        TimerStatus {
+            timer_name: self.name.clone(),
+            plan_name: self.plan.name.clone(),
+            state: self.state,
+            phase_index: index,
+            phase_kind: step.kind,
+            phase_name: step.name.clone(),
+            phase_elapsed_seconds: elapsed_seconds.saturating_sub(phase_end - step.duration_seconds),
+            phase_remaining_seconds: ceil_seconds(seconds(phase_end).saturating_sub(elapsed)),
+            elapsed_seconds,
+            remaining_seconds: ceil_seconds(total.saturating_sub(elapsed)),
+            total_seconds: self.plan.total_seconds(),
+            next_action,
+        }
+    }
+
+    /// Actions not started yet, with the process time left until each one
+    pub fn upcoming(&self, now: Instant) -> Vec<(ProcessAction, Duration)> {
+        if !self.is_active() {
+            return Vec::new();
+        }
+
+        let elapsed = self.elapsed(now);
+        self.actions
+            .iter()
+            .filter(|action| seconds(action.start_seconds) >= elapsed)
+            .map(|action| (action.clone(), seconds(action.start_seconds) - elapsed))
+            .collect()
+    }
+
+    /// Process time, which stands still while paused and jumps forward on skips
+    pub fn elapsed(&self, now: Instant) -> Duration {
+        let running = self.resumed_at.map(|resumed_at| now.saturating_duration_since(resumed_at)).unwrap_or_default();
+        (self.base + running).min(seconds(self.plan.total_seconds()))
+    }
+
+    fn stop(&mut self, now: Instant) {
+        self.base = self.elapsed(now);
+        self.resumed_at = None;
+    }
+
+    fn is_due(&self, at: Duration, elapsed: Duration) -> bool {
+        at >= self.cursor && at <= elapsed
+    }
+
+    fn phase_index(&self, elapsed: Duration) -> usize {
+        (0..self.plan.steps.len())
+            .find(|&index| seconds(self.phase_end(index)) > elapsed)
+            .unwrap_or(self.plan.steps.len() - 1)
+    }
+
+    fn phase_end(&self, index: usize) -> u32 {
+        self.plan.steps[..=index].iter().map(|step| step.duration_seconds).sum()
+    }
+
+    fn drain(&self, index: usize) -> Option<ProcessAction> {
+        self.actions
+            .iter()
+            .find(|action| action.step_index == index && action.kind == ProcessActionKind::Drain)
+            .cloned()
+    }
+
+    // Sends any cues already due before the timer is changed
+    fn flush(&mut self, now: Instant) -> Result<Vec<TimerEvent>, TimerError> {
+        if self.state != TimerState::Running {
+            return Err(TimerError::NotRunning);
+        }
+        Ok(self.poll(now))
+    }
+
+    fn event(&self, kind: TimerEventKind, action: Option<ProcessAction>, now: Instant) -> TimerEvent {
+        TimerEvent {
+            kind,
+            action,
+            status: self.status(now),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+type EventSink = dyn Fn(&TimerEvent) + Send + Sync;
+#[cfg(feature = "serde")]
+type Clock = dyn Fn() -> Instant + Send + Sync;
+
+// A timer along with the wall-clock times needed to save it
+#[cfg(feature = "serde")]
+struct TrackedTimer {
+    timer: ProcessTimer,
+    started_at: DateTime<Utc>,
+    paused_at: Option<DateTime<Utc>>,
+    recovered: bool,
+}
+
+/// Owns every named process timer and a worker thread that sends their cues to the sink.
+/// With a store, timers are saved after every change and can be brought back with [`TimerService::restore`].
+#[cfg(feature = "serde")]
+pub struct TimerService {
+    timers: Arc<Mutex<Vec<TrackedTimer>>>,
+    store: Option<Arc<TimerStore>>,
+    sink: Arc<EventSink>,
+    clock: Arc<Clock>,
+    // Why restoring failed, until the app has been told through in_flight
+    restore_failure: Mutex<Option<String>>,
+}
+
+#[cfg(feature = "serde")]
+impl TimerService {
+    pub fn new(store: Option<TimerStore>, sink: impl Fn(&TimerEvent) + Send + Sync + 'static) -> Self {
+        Self::with_clock(store, sink, Instant::now)
+    }
+
+    /// Like [`TimerService::new`], but reads the time from `clock` instead of the system,
+    /// so a caller can step time forward by hand
+    pub fn with_clock(
+        store: Option<TimerStore>,
+        sink: impl Fn(&TimerEvent) + Send + Sync + 'static,
+        clock: impl Fn() -> Instant + Send + Sync + 'static,
+    ) -> Self {
+        let service = TimerService {
+            timers: Arc::new(Mutex::new(Vec::new())),
+            store: store.map(Arc::new),
+            sink: Arc::new(sink),
+            clock: Arc::new(clock),
+            restore_failure: Mutex::new(None),
+        };
+
+        let timers = Arc::downgrade(&service.timers);
+        let store = service.store.clone();
+        let sink = Arc::clone(&service.sink);
+        let clock = Arc::clone(&service.clock);
+        thread::spawn(move || {
+            // Stops once the service has been dropped
+            while let Some(timers) = timers.upgrade() {
+                let mut guard = timers.lock().unwrap();
+                let now = clock();
+                let events: Vec<TimerEvent> = guard
+                    .iter_mut()
+                    .flat_map(|tracked| tracked.timer.poll(now))
+                    .collect();
+                if events.iter().any(|event| event.kind == TimerEventKind::Completed) {
+                    // Nobody to report to here; the next change saves again, and a timer
+                    // restored past its end completes straight away
+                    let _ = persist(store.as_deref(), &guard, now);
+                }
+                drop(guard);
+                drop(timers);
+
+                for event in &events {
+                    sink(event);
+                }
+                thread::sleep(POLL_INTERVAL);
+            }
+        });
+
+        service
+    }
+
+    /// Loads the timers saved in the store, e.g. after a restart or crash, and returns how many
+    /// there were. Timers that were running kept counting while the app was closed.
+    /// A failure is also kept for [`TimerService::in_flight`], which is how the app hears about it.
+    pub fn restore(&self) -> Result<usize, TimerError> {
+        let Some(store) = self.store.as_deref() else {
+            return Ok(0);
+        };
+
+        let snapshots = store.load().map_err(|e| {
+            *self.restore_failure.lock().unwrap() = Some(e.to_string());
+            TimerError::RestoreFailed(e.to_string())
+        })?;
+        let now = (self.clock)();
+        let restored: Vec<TrackedTimer> = snapshots
+            .into_iter()
+            .map(|snapshot| TrackedTimer::restore(snapshot, now))
+            .collect();
+        let count = restored.len();
+        self.timers.lock().unwrap().extend(restored);
+        Ok(count)
+    }
+
+    pub fn start(&self, request: TimerRequest) -> Result<TimerStatus, TimerError> {
+        let now = (self.clock)();
+        let (timer, event) = ProcessTimer::start(request, now)?;
+
+        let mut guard = self.timers.lock().unwrap();
+        if guard.iter().any(|tracked| tracked.timer.name() == timer.name() && tracked.timer.is_active()) {
+            return Err(TimerError::NameInUse(timer.name().to_string()));
+        }
+
+        // Finished timers are kept for their status until another one starts
+        guard.retain(|tracked| {
+            (tracked.timer.is_active() || tracked.recovered) && tracked.timer.name() != timer.name()
+        });
+        guard.push(TrackedTimer {
+            timer,
+            started_at: Utc::now(),
+            paused_at: None,
+            recovered: false,
+        });
+        let saved = persist(self.store.as_deref(), &guard, now);
+        drop(guard);
+
+        (self.sink)(&event);
+        saved?;
+        Ok(event.status)
+    }
+
+    pub fn pause(&self, name: &str) -> Result<TimerStatus, TimerError> {
+        self.update(name, |timer, now| timer.pause(now))
+    }
+
+    pub fn resume(&self, name: &str) -> Result<TimerStatus, TimerError> {
+        self.update(name, |timer, now| timer.resume(now))
+    }
+
+    pub fn skip(&self, name: &str) -> Result<TimerStatus, TimerError> {
+        self.update(name, |timer, now| timer.skip(now))
+    }
+
+    pub fn adjust(&self, name: &str, delta_seconds: i32) -> Result<TimerStatus, TimerError> {
+        self.update(name, |timer, now| timer.adjust(now, delta_seconds))
+    }
+
+    pub fn cancel(&self, name: &str) -> Result<TimerStatus, TimerError> {
+        self.update(name, |timer, now| timer.cancel(now))
+    }
+
+    pub fn status(&self, name: &str) -> Option<TimerStatus> {
+        self.timers
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|tracked| tracked.timer.name() == name)
+            .map(|tracked| tracked.timer.status((self.clock)()))
+    }
+
+    pub fn statuses(&self) -> Vec<TimerStatus> {
+        let now = (self.clock)();
+        self.timers
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|tracked| tracked.timer.status(now))
+            .collect()
+    }
+
+    /// The next actions across every running or paused timer, soonest first
+    pub fn upcoming(&self, limit: usize) -> Vec<UpcomingAction> {
+        let now = (self.clock)();
+        let guard = self.timers.lock().unwrap();
+        let mut upcoming: Vec<UpcomingAction> = guard
+            .iter()
+            .flat_map(|tracked| {
+                let timer = &tracked.timer;
+                let paused = timer.state() == TimerState::Paused;
+                timer.upcoming(now).into_iter().map(move |(action, due_in)| UpcomingAction {
+                    timer_name: timer.name().to_string(),
+                    step_name: timer.plan().steps[action.step_index].name.clone(),
+                    action,
+                    due_in_seconds: ceil_seconds(due_in),
+                    paused,
+                })
+            })
+            .collect();
+
+        // Running timers first at equal times, since paused ones only move once resumed
+        upcoming.sort_by_key(|entry| (entry.due_in_seconds, entry.paused));
+        upcoming.truncate(limit);
+        upcoming
+    }
+
+    /// Timers that are still running or paused, plus any restored after a restart
+    /// that the app has not picked up again yet. If restoring failed, the first call
+    /// returns that error instead.
+    pub fn in_flight(&self) -> Result<Vec<InFlightTimer>, TimerError> {
+        if let Some(failure) = self.restore_failure.lock().unwrap().take() {
+            return Err(TimerError::RestoreFailed(failure));
+        }
+
+        let now = (self.clock)();
+        Ok(self
+            .timers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|tracked| tracked.timer.is_active() || tracked.recovered)
+            .map(|tracked| tracked.in_flight(now))
+            .collect())
+    }
+
+    /// Hands a restored timer back to the app, resending its current status
+    pub fn reattach(&self, name: &str) -> Result<InFlightTimer, TimerError> {
+        let now = (self.clock)();
+        let mut guard = self.timers.lock().unwrap();
+        let tracked = guard
+            .iter_mut()
+            .find(|tracked| tracked.timer.name() == name && (tracked.timer.is_active() || tracked.recovered))
+            .ok_or_else(|| TimerError::NotFound(name.to_string()))?;
+        tracked.recovered = false;
+
+        let in_flight = tracked.in_flight(now);
+        drop(guard);
+
+        (self.sink)(&TimerEvent {
+            kind: TimerEventKind::Reattached,
+            action: None,
+            status: in_flight.status.clone(),
+        });
+        Ok(in_flight)
+    }
+
+    // Applies a change to one timer and saves, then sends its events once the lock is released
+    fn update(
+        &self,
+        name: &str,
+        change: impl FnOnce(&mut ProcessTimer, Instant) -> Result<Vec<TimerEvent>, TimerError>,
+    ) -> Result<TimerStatus, TimerError> {
+        let now = (self.clock)();
+        let mut guard = self.timers.lock().unwrap();
+        let tracked = guard
+            .iter_mut()
+            .find(|tracked| tracked.timer.name() == name)
+            .ok_or_else(|| TimerError::NotFound(name.to_string()))?;
+        let events = change(&mut tracked.timer, now)?;
+        let status = tracked.timer.status(now);
+        tracked.paused_at = match status.state {
+            TimerState::Paused => tracked.paused_at.or_else(|| Some(Utc::now())),
+            _ => None,
+        };
+        let saved = persist(self.store.as_deref(), &guard, now);
+        drop(guard);
+
+        for event in &events {
+            (self.sink)(event);
+        }
+        saved?;
+        Ok(status)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TrackedTimer {
+    // A timer that was running keeps counting through the time the app was closed
+    fn restore(snapshot: TimerSnapshot, now: Instant) -> Self {
+        let downtime = if snapshot.state == TimerState::Running {
+            (Utc::now() - snapshot.saved_at).to_std().unwrap_or_default()
+        } else {
+            Duration::ZERO
+        };
+        let elapsed = Duration::from_millis(snapshot.elapsed_ms) + downtime;
+        let name = if snapshot.name.is_empty() {
+            snapshot.plan.name.clone()
+        } else {
+            snapshot.name
+        };
+
+        TrackedTimer {
+            timer: ProcessTimer::restore(name, snapshot.plan, snapshot.lead_in_seconds, snapshot.state, elapsed, now),
+            started_at: snapshot.started_at,
+            paused_at: snapshot.paused_at,
+            recovered: true,
+        }
+    }
+
+    fn snapshot(&self, now: Instant) -> TimerSnapshot {
+        let status = self.timer.status(now);
+        TimerSnapshot {
+            name: self.timer.name().to_string(),
+            plan: self.timer.plan().clone(),
+            lead_in_seconds: self.timer.lead_in_seconds(),
+            state: status.state,
+            started_at: self.started_at,
+            paused_at: self.paused_at,
+            saved_at: Utc::now(),
+            elapsed_ms: self.timer.elapsed(now).as_millis() as u64,
+            phase_index: status.phase_index,
+        }
+    }
+
+    fn in_flight(&self, now: Instant) -> InFlightTimer {
+        InFlightTimer {
+            started_at: self.started_at,
+            paused_at: self.paused_at,
+            recovered: self.recovered,
+            plan: self.timer.plan().clone(),
+            status: self.timer.status(now),
+        }
+    }
+}
+
+// Saves the timers that are running or paused, clearing the file once none are left
+#[cfg(feature = "serde")]
+fn persist(store: Option<&TimerStore>, timers: &[TrackedTimer], now: Instant) -> Result<(), TimerStoreError> {
+    let Some(store) = store else {
+        return Ok(());
+    };
+
+    let snapshots: Vec<TimerSnapshot> = timers
+        .iter()
+        .filter(|tracked| tracked.timer.is_active())
+        .map(|tracked| tracked.snapshot(now))
+        .collect();
+    store.save(&snapshots)
+}
+
+fn seconds(value: u32) -> Duration {
+    Duration::from_secs(u64::from(value))
+}
+
+// Countdowns show 0:01 until the last second has fully passed
+fn ceil_seconds(duration: Duration) -> u32 {
+    duration.as_secs_f64().ceil() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(kind: ProcessStepKind, duration_seconds: u32) -> ProcessStep {
+        ProcessStep {
+            kind,
+            name: format!("{:?}", kind),
+            duration_seconds,
+            temperature: None,
+            agitation: None,
+            pour_seconds: 10,
+            drain_seconds: 10,
+        }
+    }
+
+    // Pours at 0 and 60, drains at 50 and 80, 90 seconds in all
+    fn request() -> TimerRequest {
+        TimerRequest {
+            name: Some("tank".to_string()),
+            plan: ProcessPlan {
+                name: "HP5+ in D-76".to_string(),
+                steps: vec![step(ProcessStepKind::Developer, 60), step(ProcessStepKind::StopBath, 30)],
+            },
+            lead_in_seconds: Some(5),
+        }
+    }
+
+    fn after(start: Instant, secs: u64) -> Instant {
+        start + Duration::from_secs(secs)
+    }
+
+    fn cues(events: &[TimerEvent]) -> Vec<(TimerEventKind, Option<ProcessActionKind>, u32)> {
+        events
+            .iter()
+            .map(|event| (event.kind, event.action.as_ref().map(|action| action.kind), event.action.as_ref().map_or(0, |action| action.start_seconds)))
+            .collect()
+    }
+
+    #[test]
+    fn cues_arrive_in_process_order() {
+        let start = Instant::now();
+        let (mut timer, started) = ProcessTimer::start(request(), start).unwrap();
+        assert_eq!(started.kind, TimerEventKind::Started);
+        assert_eq!(cues(&timer.poll(start)), vec![(TimerEventKind::ActionDue, Some(ProcessActionKind::Pour), 0)]);
+
+        // One late poll still sends everything it slept through, in order
+        assert_eq!(
+            cues(&timer.poll(after(start, 62))),
+            vec![
+                (TimerEventKind::LeadIn, Some(ProcessActionKind::Drain), 50),
+                (TimerEventKind::ActionDue, Some(ProcessActionKind::Drain), 50),
+                (TimerEventKind::LeadIn, Some(ProcessActionKind::Pour), 60),
+                (TimerEventKind::PhaseChanged, None, 0),
+                (TimerEventKind::ActionDue, Some(ProcessActionKind::Pour), 60),
+                (TimerEventKind::Tick, None, 0),
+            ]
+        );
+        assert!(timer.poll(after(start, 62)).is_empty());
+
+        let finished = timer.poll(after(start, 200));
+        assert_eq!(finished.last().unwrap().kind, TimerEventKind::Completed);
+        assert_eq!(timer.state(), TimerState::Completed);
+        assert_eq!(timer.status(after(start, 300)).elapsed_seconds, 90);
+    }
+
+    #[test]
+    fn paused_timers_stand_still() {
+        let start = Instant::now();
+        let (mut timer, _) = ProcessTimer::start(request(), start).unwrap();
+        timer.poll(start);
+
+        let paused = timer.pause(after(start, 20)).unwrap();
+        assert_eq!(paused.last().unwrap().kind, TimerEventKind::Paused);
+        assert!(timer.poll(after(start, 100)).is_empty());
+        assert_eq!(timer.status(after(start, 100)).elapsed_seconds, 20);
+        assert!(timer.upcoming(after(start, 100)).iter().all(|(action, due_in)| *due_in == seconds(action.start_seconds) - seconds(20)));
+        assert!(matches!(timer.pause(after(start, 100)), Err(TimerError::NotRunning)));
+
+        let resumed = timer.resume(after(start, 100)).unwrap();
+        assert_eq!(cues(&resumed), vec![(TimerEventKind::Resumed, None, 0)]);
+        assert!(matches!(timer.resume(after(start, 100)), Err(TimerError::NotPaused)));
+
+        let status = timer.status(after(start, 110));
+        assert_eq!((status.state, status.elapsed_seconds, status.remaining_seconds), (TimerState::Running, 30, 60));
+    }
+
+    #[test]
+    fn skipping_drops_the_rest_of_the_phase() {
+        let start = Instant::now();
+        let (mut timer, _) = ProcessTimer::start(request(), start).unwrap();
+        timer.poll(after(start, 20));
+
+        let skipped = timer.skip(after(start, 20)).unwrap();
+        assert_eq!(
+            cues(&skipped),
+            vec![
+                (TimerEventKind::Skipped, None, 0),
+                (TimerEventKind::PhaseChanged, None, 0),
+                (TimerEventKind::ActionDue, Some(ProcessActionKind::Pour), 60),
+            ]
+        );
+
+        let status = timer.status(after(start, 25));
+        assert_eq!((status.phase_index, status.elapsed_seconds, status.phase_elapsed_seconds), (1, 65, 5));
+    }
+
+    #[test]
+    fn adjusting_moves_the_current_phase_and_everything_after_it() {
+        let start = Instant::now();
+        let (mut timer, _) = ProcessTimer::start(request(), start).unwrap();
+        timer.poll(start);
+
+        timer.adjust(after(start, 20), 30).unwrap();
+        let status = timer.status(after(start, 20));
+        assert_eq!((status.total_seconds, status.phase_remaining_seconds), (120, 70));
+        let drains: Vec<u32> = timer
+            .upcoming(after(start, 20))
+            .into_iter()
+            .filter(|(action, _)| action.kind == ProcessActionKind::Drain)
+            .map(|(action, _)| action.start_seconds)
+            .collect();
+        assert_eq!(drains, vec![80, 110]);
+
+        // Never below the time already spent in the phase, which then ends straight away
+        timer.adjust(after(start, 40), -1000).unwrap();
+        let status = timer.status(after(start, 40));
+        assert_eq!((status.total_seconds, status.phase_index), (70, 1));
+    }
+
+    #[test]
+    fn shortening_past_the_drain_sends_it_straight_away() {
+        let start = Instant::now();
+        let (mut timer, _) = ProcessTimer::start(request(), start).unwrap();
+        timer.poll(start);
+        timer.poll(after(start, 25));
+
+        // The drain moves from 50 to 20 seconds, which has already gone by
+        let adjusted = timer.adjust(after(start, 25), -30).unwrap();
+        assert_eq!(
+            cues(&adjusted),
+            vec![(TimerEventKind::Adjusted, None, 0), (TimerEventKind::ActionDue, Some(ProcessActionKind::Drain), 20)]
+        );
+        assert_eq!(timer.status(after(start, 25)).phase_remaining_seconds, 5);
+
+        // A drain that was already sent is not sent again
+        timer.poll(after(start, 26));
+        let adjusted = timer.adjust(after(start, 26), -1).unwrap();
+        assert_eq!(cues(&adjusted), vec![(TimerEventKind::Adjusted, None, 0)]);
+    }
+
+    #[test]
+    fn cancelled_timers_send_no_more_cues() {
+        let start = Instant::now();
+        let (mut timer, _) = ProcessTimer::start(request(), start).unwrap();
+        timer.poll(start);
+
+        let cancelled = timer.cancel(after(start, 10)).unwrap();
+        assert_eq!(cancelled.last().unwrap().kind, TimerEventKind::Cancelled);
+        assert!(timer.poll(after(start, 100)).is_empty());
+        assert!(matches!(timer.cancel(after(start, 100)), Err(TimerError::NotRunning)));
+        assert!(matches!(timer.skip(after(start, 100)), Err(TimerError::NotRunning)));
+    }
+
+    #[test]
+    fn empty_plans_are_refused() {
+        let mut request = request();
+        request.plan.steps.clear();
+        assert!(matches!(ProcessTimer::start(request, Instant::now()), Err(TimerError::EmptyPlan)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn the_service_follows_its_clock() {
+        let start = Instant::now();
+        let now = Arc::new(Mutex::new(start));
+        let clock = Arc::clone(&now);
+        let service = TimerService::with_clock(None, |_| {}, move || *clock.lock().unwrap());
+        let advance = |secs: u64| *now.lock().unwrap() += Duration::from_secs(secs);
+
+        assert_eq!(service.start(request()).unwrap().state, TimerState::Running);
+        assert!(matches!(service.start(request()), Err(TimerError::NameInUse(_))));
+
+        advance(20);
+        assert_eq!(service.pause("tank").unwrap().elapsed_seconds, 20);
+        advance(100);
+        assert_eq!(service.status("tank").unwrap().elapsed_seconds, 20);
+        assert_eq!(service.upcoming(1)[0].due_in_seconds, 30);
+        assert!(service.upcoming(1)[0].paused);
+
+        service.resume("tank").unwrap();
+        advance(10);
+        assert_eq!(service.statuses()[0].elapsed_seconds, 30);
+
+        let skipped = service.skip("tank").unwrap();
+        assert_eq!((skipped.phase_index, skipped.elapsed_seconds), (1, 60));
+        assert_eq!(service.adjust("tank", 15).unwrap().total_seconds, 105);
+        assert_eq!(service.in_flight().unwrap().len(), 1);
+
+        assert_eq!(service.cancel("tank").unwrap().state, TimerState::Cancelled);
+        assert!(service.in_flight().unwrap().is_empty());
+        assert!(matches!(service.pause("darkroom"), Err(TimerError::NotFound(_))));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn a_failed_restore_is_reported_once() {
+        let path = std::env::temp_dir().join(format!("darkroom-timers-{}.json", std::process::id()));
+        std::fs::write(&path, "not json").unwrap();
+        let service = TimerService::new(Some(TimerStore::new(path.clone())), |_| {});
+
+        assert!(matches!(service.restore(), Err(TimerError::RestoreFailed(_))));
+        assert!(matches!(service.in_flight(), Err(TimerError::RestoreFailed(_))));
+        assert!(service.in_flight().unwrap().is_empty());
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,124 @@
+use crate::models::*;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use thiserror::Error;
+
+// Numbers temporary files, so two writers never share one
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Error, Debug)]
+pub enum TimerStoreError {
+    #[error("Failed to access timer file: {0}")]
+    FileError(#[from] std::io::Error),
+    #[error("Failed to parse timer file: {0}")]
+    ParseError(#[from] serde_json::Error),
+}
+
+/// Keeps in-flight timers in a JSON file in the app data directory
+pub struct TimerStore {
+    path: PathBuf,
+}
+
+impl TimerStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn load(&self) -> Result<Vec<TimerSnapshot>, TimerStoreError> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&self.path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Writes to a temporary file of its own first, so a crash or another writer never
+    /// leaves a broken file behind
+    pub fn save(&self, snapshots: &[TimerSnapshot]) -> Result<(), TimerStoreError> {
+        if snapshots.is_empty() {
+            return match fs::remove_file(&self.path) {
+                Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+                _ => Ok(()),
+            };
+        }
+
+        let content = serde_json::to_string_pretty(snapshots)?;
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temp_path = self.path.with_extension(format!(
+            "json.{}.{}.tmp",
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let written = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temp_path)
+            .and_then(|mut file| {
+                file.write_all(content.as_bytes())?;
+                file.sync_all()
+            })
+            .and_then(|_| fs::rename(&temp_path, &self.path));
+        if written.is_err() {
+            let _ = fs::remove_file(&temp_path);
+        }
+        Ok(written?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::sync::Arc;
+    use std::thread;
+
+    fn snapshot(name: &str) -> TimerSnapshot {
+        TimerSnapshot {
+            name: name.to_string(),
+            plan: ProcessPlan { name: "HP5+ in D-76".to_string(), steps: Vec::new() },
+            lead_in_seconds: 10,
+            state: TimerState::Running,
+            started_at: Utc::now(),
+            paused_at: None,
+            saved_at: Utc::now(),
+            elapsed_ms: 1500,
+            phase_index: 0,
+        }
+    }
+
+    #[test]
+    fn concurrent_saves_always_leave_a_whole_file() {
+        let directory = std::env::temp_dir().join(format!("darkroom-timer-store-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        let store = Arc::new(TimerStore::new(directory.join("process_timers.json")));
+
+        let writers: Vec<_> = (0..8)
+            .map(|writer| {
+                let store = Arc::clone(&store);
+                thread::spawn(move || {
+                    for _ in 0..20 {
+                        store.save(&[snapshot(&format!("tank {}", writer))]).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let saved = store.load().unwrap();
+        assert_eq!(saved.len(), 1);
+        assert!(saved[0].name.starts_with("tank "));
+        assert_eq!(fs::read_dir(&directory).unwrap().count(), 1, "temporary files were left behind");
+
+        store.save(&[]).unwrap();
+        store.save(&[]).unwrap();
+        assert!(store.load().unwrap().is_empty());
+        let _ = fs::remove_dir_all(&directory);
+    }
+}